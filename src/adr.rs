@@ -0,0 +1,340 @@
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::atomic::write_atomic;
+use crate::lock::lock_notes;
+use crate::output::{render_json, render_table, render_tsv};
+use crate::resolve::project_candidates;
+use crate::{NotesPaths, OutputFormat, ResolvedProject, resolve_project, section, slugify};
+
+/// Status given to new ADRs unless `--status` says otherwise.
+pub const DEFAULT_ADR_STATUS: &str = "Proposed";
+
+/// One `adr-NNNN-<slug>.md` file in a project directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adr {
+    /// Id of the project the ADR belongs to.
+    pub project: String,
+    /// Numbered per project, starting at 1.
+    pub number: u32,
+    pub title: String,
+    /// First line of the `## Status` section, with links reduced to their text.
+    pub status: String,
+    pub supersedes: Option<u32>,
+    pub superseded_by: Option<u32>,
+    /// True when the project lives in `Archives/Projects/`.
+    pub archived: bool,
+    pub path: PathBuf,
+}
+
+impl Adr {
+    /// `ADR-0003`.
+    pub fn label(&self) -> String {
+        adr_label(self.number)
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn link(&self) -> String {
+        format!("[{}: {}]({})", self.label(), self.title, self.file_name())
+    }
+}
+
+/// Create the next numbered ADR in a project's directory with Status,
+/// Context, Decision and Consequences sections.
+///
+/// With `supersedes`, the new ADR links back to that ADR of the same project,
+/// whose `## Status` section is replaced with a "Superseded by" link.
+pub fn new_adr(
+    paths: &NotesPaths,
+    project: &str,
+    title: &str,
+    status: &str,
+    supersedes: Option<u32>,
+) -> Result<PathBuf> {
+    let project = resolve_project(paths, project)?;
+    let title = title.trim();
+    if !title.chars().any(|ch| ch.is_ascii_alphanumeric()) {
+        bail!("ADR title must contain letters or digits");
+    }
+    let status = status.trim();
+    if status.is_empty() || status.contains('\n') {
+        bail!("ADR status must be a single non-empty line");
+    }
+    let slug = slugify(title)?;
+
+    // Held across numbering and writing, so concurrent calls never share a number.
+    let _lock = lock_notes(&paths.root)?;
+    let existing = project_adrs(&project)?;
+    let number = existing.last().map_or(1, |adr| adr.number + 1);
+    let path = project.dir.join(format!("adr-{number:04}-{slug}.md"));
+    let adr = Adr {
+        project: project.id.clone(),
+        number,
+        title: title.to_string(),
+        status: status.to_string(),
+        supersedes,
+        superseded_by: None,
+        archived: project.archived,
+        path: path.clone(),
+    };
+
+    let old = match supersedes {
+        Some(old_number) => {
+            let old = existing
+                .iter()
+                .find(|adr| adr.number == old_number)
+                .with_context(|| format!("No {} in {}", adr_label(old_number), project.dir_name))?;
+            if let Some(newer) = old.superseded_by {
+                bail!(
+                    "{} is already superseded by {}",
+                    old.label(),
+                    adr_label(newer)
+                );
+            }
+            let original = fs::read_to_string(&old.path)
+                .with_context(|| format!("Failed to read ADR {}", old.path.display()))?;
+            let updated = replace_status(&original, &format!("Superseded by {}", adr.link()))
+                .with_context(|| format!("Cannot supersede {}", old.path.display()))?;
+            Some((old, original, updated))
+        }
+        None => None,
+    };
+
+    let mut status_section = status.to_string();
+    if let Some((old, _, _)) = &old {
+        status_section.push_str(&format!("\n\nSupersedes {}", old.link()));
+    }
+    let content = format!(
+        "# {label}: {title}\n\n**Date**: {date}\n\n## Status\n{status_section}\n\n## Context\n- \n\n## Decision\n- \n\n## Consequences\n- \n",
+        label = adr.label(),
+        date = Local::now().format("%Y-%m-%d"),
+    );
+
+    // Mark the old ADR first and restore it if the new one cannot be written,
+    // so a "Supersedes" link never exists without its "Superseded by" side.
+    if let Some((old, _, updated)) = &old {
+        write_atomic(&old.path, updated)
+            .with_context(|| format!("Failed to write ADR {}", old.path.display()))?;
+    }
+    if let Err(err) = write_atomic(&path, content) {
+        let err = err.context(format!("Failed to write ADR {}", path.display()));
+        if let Some((old, original, _)) = &old
+            && let Err(restore) = write_atomic(&old.path, original)
+        {
+            return Err(err.context(format!(
+                "Failed to restore ADR {} ({restore}); it is still marked superseded",
+                old.path.display()
+            )));
+        }
+        return Err(err);
+    }
+    Ok(path)
+}
+
+/// ADRs of one project, or of every project in `Projects/` and
+/// `Archives/Projects/`, ordered by project and number.
+pub fn list_adrs(paths: &NotesPaths, project: Option<&str>) -> Result<Vec<Adr>> {
+    let projects = match project {
+        Some(project) => vec![resolve_project(paths, project)?],
+        None => project_candidates(paths)?,
+    };
+    let mut adrs = Vec::new();
+    for project in &projects {
+        adrs.extend(project_adrs(project)?);
+    }
+    Ok(adrs)
+}
+
+fn adr_label(number: u32) -> String {
+    format!("ADR-{number:04}")
+}
+
+fn project_adrs(project: &ResolvedProject) -> Result<Vec<Adr>> {
+    let file_name = Regex::new(r"^adr-(\d+)-.+\.md$").expect("valid ADR file regex");
+    let mut adrs = Vec::new();
+    for entry in fs::read_dir(&project.dir)
+        .with_context(|| format!("Failed to read {}", project.dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(number) = file_name
+            .captures(&name)
+            .and_then(|captures| captures[1].parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ADR {}", path.display()))?;
+        adrs.push(parse_adr(project, number, path, &content));
+    }
+    adrs.sort_by_key(|adr| adr.number);
+    Ok(adrs)
+}
+
+fn parse_adr(project: &ResolvedProject, number: u32, path: PathBuf, content: &str) -> Adr {
+    let link = Regex::new(r"\[([^\]]*)\]\([^)]*\)").expect("valid link regex");
+    let adr_link = Regex::new(r"\]\(adr-(\d+)-[^)]*\)").expect("valid ADR link regex");
+
+    let heading = format!("{}:", adr_label(number));
+    let title = content
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|line| line.trim_start_matches(heading.as_str()).trim().to_string())
+        .unwrap_or_default();
+
+    let (lines, _) = crate::split_lines(content);
+    let body = section::find_section(&lines, "## Status")
+        .map(|(_, range)| {
+            let end = section::section_insert_point(&lines, &range);
+            lines[range.start + 1..end].to_vec()
+        })
+        .unwrap_or_default();
+    // `Supersedes [...](adr-0001-...)` / `Superseded by [...](adr-0003-...)`.
+    let linked_number = |prefix: &str| {
+        body.iter()
+            .map(|line| line.trim_start())
+            .find(|line| line.starts_with(prefix))
+            .and_then(|line| adr_link.captures(line))
+            .and_then(|captures| captures[1].parse::<u32>().ok())
+    };
+    let status = body
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .map(|line| link.replace_all(line, "$1").into_owned())
+        .unwrap_or_default();
+
+    Adr {
+        project: project.id.clone(),
+        number,
+        title,
+        status,
+        supersedes: linked_number("Supersedes "),
+        superseded_by: linked_number("Superseded by "),
+        archived: project.archived,
+        path,
+    }
+}
+
+/// Replace the body of the `## Status` section with one line.
+fn replace_status(content: &str, status: &str) -> Result<String> {
+    let (mut lines, trailing_newline) = crate::split_lines(content);
+    let (_, range) = section::find_section(&lines, "## Status")?;
+    let end = section::section_insert_point(&lines, &range);
+    lines.splice(range.start + 1..end, [status.to_string()]);
+    Ok(crate::join_lines(&lines, trailing_newline))
+}
+
+pub fn render_adrs(adrs: &[Adr], format: Option<OutputFormat>) -> String {
+    let rows = adrs
+        .iter()
+        .map(|adr| {
+            vec![
+                adr.project.clone(),
+                adr.label(),
+                adr.status.clone(),
+                adr.title.clone(),
+                adr.path.display().to_string(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    match format {
+        Some(OutputFormat::Json) => render_json(&Value::Array(
+            adrs.iter()
+                .map(|adr| {
+                    json!({
+                        "project": adr.project,
+                        "number": adr.number,
+                        "id": adr.label(),
+                        "title": adr.title,
+                        "status": adr.status,
+                        "supersedes": adr.supersedes.map(adr_label),
+                        "superseded_by": adr.superseded_by.map(adr_label),
+                        "archived": adr.archived,
+                        "path": adr.path,
+                    })
+                })
+                .collect(),
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        _ if adrs.is_empty() => "No ADRs found.\n".to_string(),
+        None => render_tsv(&rows),
+        Some(OutputFormat::Table) => {
+            render_table(&["project", "adr", "status", "title", "path"], &rows)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn numbers_adrs_and_links_superseded_ones() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        let dir = paths.projects_dir.join("proj-4-billing");
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(paths.archives_projects_dir.join("proj-2-search")).unwrap();
+
+        let first = new_adr(&paths, "PROJ-4", "Use Postgres", "Accepted", None).unwrap();
+        assert_eq!(first, dir.join("adr-0001-use-postgres.md"));
+        let content = fs::read_to_string(&first).unwrap();
+        assert!(content.starts_with("# ADR-0001: Use Postgres\n\n**Date**: "));
+        assert!(content.contains(
+            "\n## Status\nAccepted\n\n## Context\n- \n\n## Decision\n- \n\n## Consequences\n- \n"
+        ));
+
+        // A directory in the way makes writing the new ADR fail: the old one is restored.
+        let blocker = dir.join("adr-0002-use-mysql.md");
+        fs::create_dir_all(&blocker).unwrap();
+        assert!(new_adr(&paths, "billing", "Use MySQL", "Proposed", Some(1)).is_err());
+        assert_eq!(fs::read_to_string(&first).unwrap(), content);
+        fs::remove_dir(&blocker).unwrap();
+
+        let second = new_adr(&paths, "billing", "Use MySQL", "Proposed", Some(1)).unwrap();
+        assert_eq!(second, dir.join("adr-0002-use-mysql.md"));
+        assert!(fs::read_to_string(&second).unwrap().contains(
+            "## Status\nProposed\n\nSupersedes [ADR-0001: Use Postgres](adr-0001-use-postgres.md)\n\n## Context"
+        ));
+        assert!(fs::read_to_string(&first).unwrap().contains(
+            "## Status\nSuperseded by [ADR-0002: Use MySQL](adr-0002-use-mysql.md)\n\n## Context"
+        ));
+        assert!(new_adr(&paths, "PROJ-4", "Use SQLite", "Proposed", Some(1)).is_err());
+        assert!(new_adr(&paths, "PROJ-4", "Use SQLite", "Proposed", Some(9)).is_err());
+
+        new_adr(&paths, "PROJ-2", "Drop Solr", "Accepted", None).unwrap();
+        let adrs = list_adrs(&paths, None).unwrap();
+        assert_eq!(
+            adrs.iter()
+                .map(|adr| (adr.project.as_str(), adr.number, adr.status.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("PROJ-4", 1, "Superseded by ADR-0002: Use MySQL"),
+                ("PROJ-4", 2, "Proposed"),
+                ("PROJ-2", 1, "Accepted"),
+            ]
+        );
+        assert_eq!(adrs[0].superseded_by, Some(2));
+        assert_eq!(adrs[1].supersedes, Some(1));
+        assert_eq!(adrs[1].title, "Use MySQL");
+        assert!(adrs[2].archived);
+        assert_eq!(list_adrs(&paths, Some("PROJ-2")).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Replace `path` with `contents` without ever exposing a partially written file.
+///
+/// The data goes to a temp file in the same directory, is fsynced, then renamed
+/// over the target; the directory is fsynced so the rename itself survives a
+/// crash. Symlinked targets are written through, and existing permissions kept.
+pub(crate) fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let target = match fs::canonicalize(path) {
+        Ok(resolved) => resolved,
+        Err(_) => path.to_path_buf(),
+    };
+    let temp = temp_path(&target)?;
+
+    let result = write_temp(&target, &temp, contents.as_ref()).and_then(|()| {
+        fs::rename(&temp, &target)
+            .with_context(|| format!("Failed to replace {}", target.display()))
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+
+    sync_parent(&target);
+    Ok(())
+}
+
+fn temp_path(target: &Path) -> Result<PathBuf> {
+    let name = target
+        .file_name()
+        .with_context(|| format!("Path must include a file name: {}", target.display()))?;
+    let temp_name = format!(
+        ".{}.pman-{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    );
+    Ok(target.with_file_name(temp_name))
+}
+
+fn write_temp(target: &Path, temp: &Path, contents: &[u8]) -> Result<()> {
+    // A stale temp file from an earlier crash of a process with the same pid.
+    if temp.is_file() {
+        let _ = fs::remove_file(temp);
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp)
+        .with_context(|| format!("Failed to create temp file {}", temp.display()))?;
+    if let Ok(metadata) = fs::metadata(target) {
+        let _ = file.set_permissions(metadata.permissions());
+    }
+    file.write_all(contents)
+        .with_context(|| format!("Failed to write temp file {}", temp.display()))?;
+    file.sync_all()
+        .with_context(|| format!("Failed to sync temp file {}", temp.display()))
+}
+
+#[cfg(unix)]
+fn sync_parent(target: &Path) {
+    if let Some(parent) = target.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_target: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn replaces_contents_without_leaving_temp_files() {
+        let temp = tempdir().unwrap();
+        let file = temp.path().join("note.md");
+        fs::write(&file, "old\n").unwrap();
+
+        write_atomic(&file, "new\n").unwrap();
+        write_atomic(&temp.path().join("fresh.md"), "fresh\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new\n");
+        let mut names = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<String>>();
+        names.sort();
+        assert_eq!(names, vec!["fresh.md", "note.md"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_symlinks() {
+        let temp = tempdir().unwrap();
+        let real = temp.path().join("real.md");
+        let link = temp.path().join("link.md");
+        fs::write(&real, "old\n").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        write_atomic(&link, "new\n").unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&real).unwrap(), "new\n");
+    }
+
+    #[test]
+    fn failed_write_keeps_original() {
+        let temp = tempdir().unwrap();
+        let file = temp.path().join("note.md");
+        fs::write(&file, "old\n").unwrap();
+        fs::create_dir(temp_path(&file).unwrap()).unwrap();
+
+        assert!(write_atomic(&file, "new\n").is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old\n");
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::atomic::write_atomic;
+use crate::lock::lock_notes;
+use crate::resolve::path_in_project;
+use crate::template::{load_template, lookup_template, render_template};
+use crate::{NotesPaths, TemplateVar, resolve_project};
+
+/// Placeholders filled in by `pman note add`; `--var` may not redefine them.
+const NOTE_PLACEHOLDERS: [&str; 4] = ["id", "name", "title", "created"];
+
+/// Create `<name>.md` inside a project's directory.
+///
+/// The note is rendered from `template`, or from the template named after the
+/// note (`design` for `design.md`) when one exists, with `{id}`, `{name}`,
+/// `{title}`, `{created}` and `vars` filled in. With `dated`, today's date is
+/// appended to the file name (`meeting-2026-03-14.md`).
+pub fn add_project_note(
+    paths: &NotesPaths,
+    project: &str,
+    name: &str,
+    template: Option<&str>,
+    vars: &[TemplateVar],
+    dated: bool,
+) -> Result<PathBuf> {
+    let project = resolve_project(paths, project)?;
+    let name = name.trim().trim_end_matches(".md");
+    let stem = Path::new(name)
+        .file_name()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty() && !stem.starts_with('.'))
+        .with_context(|| format!("Invalid note name {name:?}"))?;
+    if let Some(var) = vars
+        .iter()
+        .find(|var| NOTE_PLACEHOLDERS.contains(&var.key.as_str()))
+    {
+        bail!(
+            "Template variable {} is set by pman and cannot be overridden",
+            var.key
+        );
+    }
+
+    let created = Local::now().format("%Y-%m-%d").to_string();
+    let mut title = stem.replace(['-', '_'], " ");
+    if let Some(first) = title.get(..1) {
+        title.replace_range(..1, &first.to_ascii_uppercase());
+    }
+    let file_name = if dated {
+        title.push_str(&format!(" {created}"));
+        format!("{name}-{created}.md")
+    } else {
+        format!("{name}.md")
+    };
+    let target = path_in_project(&project, Path::new(&file_name))?;
+
+    let source = match template {
+        Some(template) => Some(load_template(&paths.root, template)?),
+        None if lookup_template(&paths.root, stem)?.is_some() => {
+            Some(load_template(&paths.root, stem)?)
+        }
+        None => None,
+    };
+    let content = match source {
+        Some(source) => {
+            let mut values = vec![
+                ("id", project.id.as_str()),
+                ("name", project.name.as_str()),
+                ("title", title.as_str()),
+                ("created", created.as_str()),
+            ];
+            values.extend(
+                vars.iter()
+                    .map(|var| (var.key.as_str(), var.value.as_str())),
+            );
+            render_template(&source, &values)
+        }
+        None => format!(
+            "# {id}: {title}\n\n**Created**: {created}\n",
+            id = project.id
+        ),
+    };
+
+    let _lock = lock_notes(&paths.root)?;
+    if target.exists() {
+        bail!("Note already exists: {}", target.display());
+    }
+    create_parent(&target)?;
+    write_atomic(&target, content)
+        .with_context(|| format!("Failed to write note {}", target.display()))?;
+    Ok(target)
+}
+
+/// Copy an external file into a project's directory.
+///
+/// `dest` is relative to the project directory and defaults to the file's own
+/// name; an existing directory, or one given with a trailing `/`, keeps the
+/// name too. Existing files are only replaced with `force`.
+pub fn attach_file(
+    paths: &NotesPaths,
+    project: &str,
+    file: &Path,
+    dest: Option<&Path>,
+    force: bool,
+) -> Result<PathBuf> {
+    let project = resolve_project(paths, project)?;
+    if !file.is_file() {
+        bail!("Not a file: {}", file.display());
+    }
+    let file_name = file
+        .file_name()
+        .with_context(|| format!("Not a file: {}", file.display()))?;
+    let dest = dest.unwrap_or(Path::new(file_name));
+    let mut target = path_in_project(&project, dest)?;
+    if target.is_dir() || dest.to_string_lossy().ends_with(['/', '\\']) {
+        target.push(file_name);
+    }
+
+    let contents = fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let _lock = lock_notes(&paths.root)?;
+    if target.exists() && !force {
+        bail!(
+            "{} already exists; use --force to replace it",
+            target.display()
+        );
+    }
+    create_parent(&target)?;
+    write_atomic(&target, contents)
+        .with_context(|| format!("Failed to write {}", target.display()))?;
+    Ok(target)
+}
+
+fn create_parent(target: &Path) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn adds_templated_notes_and_attaches_files() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().join("Notes"));
+        let dir = paths.projects_dir.join("proj-3-payment-retry");
+        fs::create_dir_all(&dir).unwrap();
+        let templates = paths.root.join("Resources").join("Templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("design.md"),
+            "# {title} for {name} ({id})\n\nOwner: {owner}\n",
+        )
+        .unwrap();
+
+        let design = add_project_note(
+            &paths,
+            "PROJ-3",
+            "design",
+            None,
+            &["owner=ana".parse().unwrap()],
+            false,
+        )
+        .unwrap();
+        assert_eq!(design, dir.join("design.md"));
+        assert_eq!(
+            fs::read_to_string(&design).unwrap(),
+            "# Design for payment retry (PROJ-3)\n\nOwner: ana\n"
+        );
+        assert!(add_project_note(&paths, "PROJ-3", "design", None, &[], false).is_err());
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let meeting =
+            add_project_note(&paths, "payment-retry", "meeting", None, &[], true).unwrap();
+        assert_eq!(meeting, dir.join(format!("meeting-{today}.md")));
+        assert_eq!(
+            fs::read_to_string(&meeting).unwrap(),
+            format!("# PROJ-3: Meeting {today}\n\n**Created**: {today}\n")
+        );
+        assert!(add_project_note(&paths, "PROJ-3", "../escape", None, &[], false).is_err());
+
+        let diagram = temp.path().join("flow.png");
+        fs::write(&diagram, [0u8, 1, 2]).unwrap();
+        let attached = attach_file(&paths, "PROJ-3", &diagram, None, false).unwrap();
+        assert_eq!(attached, dir.join("flow.png"));
+        assert_eq!(fs::read(&attached).unwrap(), vec![0u8, 1, 2]);
+        assert!(attach_file(&paths, "PROJ-3", &diagram, None, false).is_err());
+        assert!(attach_file(&paths, "PROJ-3", &diagram, None, true).is_ok());
+
+        fs::create_dir_all(dir.join("assets")).unwrap();
+        let nested = attach_file(&paths, "PROJ-3", &diagram, Some(Path::new("assets")), false);
+        assert_eq!(nested.unwrap(), dir.join("assets").join("flow.png"));
+        let nested = attach_file(&paths, "PROJ-3", &diagram, Some(Path::new("logs/")), false);
+        assert_eq!(nested.unwrap(), dir.join("logs").join("flow.png"));
+        assert!(
+            attach_file(
+                &paths,
+                "PROJ-3",
+                &diagram,
+                Some(Path::new("../x.png")),
+                false
+            )
+            .is_err()
+        );
+    }
+}
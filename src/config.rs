@@ -0,0 +1,492 @@
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Value};
+
+use crate::atomic::write_atomic;
+use crate::output::{render_json, render_table, render_tsv};
+use crate::{OutputFormat, StatusTransitions};
+
+/// Agents `pman init`/`update` can bridge the project skill into.
+const KNOWN_AGENTS: [&str; 2] = ["claude", "codex"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    /// Relative values resolve against the workspace (or home, for the user file).
+    Path,
+    /// A TOML array of strings; `config set` takes a comma-separated list.
+    List,
+    Number,
+}
+
+/// One setting read from env vars and config files.
+struct Setting {
+    key: &'static str,
+    env: Option<&'static str>,
+    kind: Kind,
+    default: Option<&'static str>,
+}
+
+const SETTINGS: [Setting; 9] = [
+    Setting {
+        key: "notes_dir",
+        env: Some(crate::NOTES_DIR_ENV_VAR),
+        kind: Kind::Path,
+        default: None,
+    },
+    Setting {
+        key: "vault",
+        env: Some(crate::vault::VAULT_ENV_VAR),
+        kind: Kind::Text,
+        default: None,
+    },
+    Setting {
+        key: "project_prefix",
+        env: Some(crate::PROJECT_PREFIX_ENV_VAR),
+        kind: Kind::Text,
+        default: Some(crate::DEFAULT_PROJECT_PREFIX),
+    },
+    Setting {
+        key: "default_status",
+        env: None,
+        kind: Kind::Text,
+        default: Some(crate::DEFAULT_PROJECT_STATUS),
+    },
+    Setting {
+        key: "status_transitions",
+        env: Some(crate::STATUS_TRANSITIONS_ENV_VAR),
+        kind: Kind::Text,
+        default: Some(crate::DEFAULT_STATUS_TRANSITIONS),
+    },
+    Setting {
+        key: "templates_dir",
+        env: None,
+        kind: Kind::Path,
+        default: None,
+    },
+    Setting {
+        key: "agents",
+        env: None,
+        kind: Kind::List,
+        default: None,
+    },
+    Setting {
+        key: "pager",
+        env: None,
+        kind: Kind::Text,
+        default: Some("less"),
+    },
+    Setting {
+        key: "lock_timeout",
+        env: Some(crate::lock::LOCK_TIMEOUT_ENV_VAR),
+        kind: Kind::Number,
+        default: Some("10"),
+    },
+];
+
+/// Where a setting's value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Env(&'static str),
+    Workspace(PathBuf),
+    User(PathBuf),
+    Default,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "env {name}"),
+            Self::Workspace(path) | Self::User(path) => write!(f, "{}", path.display()),
+            Self::Default => f.write_str("default"),
+        }
+    }
+}
+
+/// The effective value of one setting, for `pman config get/list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    pub key: &'static str,
+    /// `None` when unset and without a fixed default.
+    pub value: Option<String>,
+    pub source: ConfigSource,
+}
+
+/// The config files in effect: the workspace `.pman/config.toml` and the
+/// user-level `~/.config/pman/config.toml`.
+#[derive(Debug, Clone, Default)]
+struct ConfigFiles {
+    workspace: Option<PathBuf>,
+    user: Option<PathBuf>,
+}
+
+impl ConfigFiles {
+    fn discover() -> Self {
+        let workspace = workspace_dir().map(|dir| dir.join(".pman").join("config.toml"));
+        Self {
+            workspace,
+            user: user_config(),
+        }
+    }
+
+    /// Env var first, then the workspace file, then the user file.
+    fn lookup(&self, setting: &Setting) -> Result<Option<(String, ConfigSource)>> {
+        if let Some(name) = setting.env
+            && let Ok(value) = std::env::var(name)
+            && !value.trim().is_empty()
+        {
+            return Ok(Some((value, ConfigSource::Env(name))));
+        }
+
+        for (path, base, source) in [
+            (
+                &self.workspace,
+                self.workspace
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .and_then(Path::parent)
+                    .map(Path::to_path_buf),
+                ConfigSource::Workspace as fn(PathBuf) -> ConfigSource,
+            ),
+            (&self.user, home_dir(), ConfigSource::User),
+        ] {
+            let Some(path) = path.as_ref().filter(|path| path.is_file()) else {
+                continue;
+            };
+            let document = load_document(path)?;
+            if let Some(item) = document.get(setting.key) {
+                let value = item_value(setting, item)
+                    .with_context(|| format!("Invalid {} in {}", setting.key, path.display()))?;
+                let value = match (setting.kind, base) {
+                    (Kind::Path, Some(base)) => resolve_path(&value, &base),
+                    _ => value,
+                };
+                return Ok(Some((value, source(path.clone()))));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// `~/.config/pman/config.toml`, or under `$XDG_CONFIG_HOME` when set.
+pub(crate) fn user_config() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+        .map(|dir| dir.join("pman").join("config.toml"))
+}
+
+/// Effective value of `key` from env vars and config files, without defaults.
+pub(crate) fn setting(key: &str) -> Result<Option<String>> {
+    Ok(ConfigFiles::discover()
+        .lookup(find_setting(key)?)?
+        .map(|(value, _)| value))
+}
+
+/// Like [`setting`], plus a description of where the value came from.
+pub(crate) fn setting_with_source(key: &str) -> Result<Option<(String, ConfigSource)>> {
+    ConfigFiles::discover().lookup(find_setting(key)?)
+}
+
+/// Effective value of one setting, including its default.
+pub fn config_get(key: &str) -> Result<ConfigEntry> {
+    entry(&ConfigFiles::discover(), find_setting(key)?)
+}
+
+/// Every known setting with its effective value.
+pub fn config_list() -> Result<Vec<ConfigEntry>> {
+    let files = ConfigFiles::discover();
+    SETTINGS
+        .iter()
+        .map(|setting| entry(&files, setting))
+        .collect()
+}
+
+/// Write `key = value` to the workspace config (or the user config when
+/// `user` is set). An empty value removes the key. Returns the file written.
+pub fn config_set(key: &str, value: &str, user: bool) -> Result<PathBuf> {
+    let files = ConfigFiles::discover();
+    let path = if user {
+        files
+            .user
+            .context("Could not locate the user config directory; set HOME or XDG_CONFIG_HOME")?
+    } else {
+        files.workspace.context(
+            "No pman workspace (.pman/) found from the current directory; run 'pman init' or use --user",
+        )?
+    };
+    set_in_file(&path, find_setting(key)?, value)?;
+    Ok(path)
+}
+
+pub fn render_config(entries: &[ConfigEntry], format: Option<OutputFormat>) -> String {
+    let rows = entries
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.key.to_string(),
+                entry.value.clone().unwrap_or_else(|| "-".to_string()),
+                entry.source.to_string(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+    match format {
+        None => rows
+            .iter()
+            .map(|row| format!("{} = {}  ({})\n", row[0], row[1], row[2]))
+            .collect(),
+        Some(OutputFormat::Json) => render_json(&json!(
+            entries
+                .iter()
+                .map(|entry| json!({
+                    "key": entry.key,
+                    "value": entry.value,
+                    "source": entry.source.to_string(),
+                }))
+                .collect::<Vec<_>>()
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => render_table(&["key", "value", "source"], &rows),
+    }
+}
+
+fn entry(files: &ConfigFiles, setting: &Setting) -> Result<ConfigEntry> {
+    Ok(match files.lookup(setting)? {
+        Some((value, source)) => ConfigEntry {
+            key: setting.key,
+            value: Some(value),
+            source,
+        },
+        None => ConfigEntry {
+            key: setting.key,
+            value: setting.default.map(str::to_string),
+            source: ConfigSource::Default,
+        },
+    })
+}
+
+fn find_setting(key: &str) -> Result<&'static Setting> {
+    SETTINGS
+        .iter()
+        .find(|setting| setting.key == key.trim())
+        .with_context(|| {
+            format!(
+                "Unknown config key {key}; known keys: {}",
+                SETTINGS
+                    .iter()
+                    .map(|setting| setting.key)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )
+        })
+}
+
+pub(crate) fn load_document(path: &Path) -> Result<DocumentMut> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config {}", path.display()))?;
+    content
+        .parse::<DocumentMut>()
+        .with_context(|| format!("Failed to parse config {}", path.display()))
+}
+
+/// A config value as the string pman uses internally; lists are comma-joined.
+fn item_value(setting: &Setting, item: &Item) -> Result<String> {
+    let value = item.as_value().context("expected a value, not a table")?;
+    match (setting.kind, value) {
+        (Kind::Text | Kind::Path, Value::String(value)) => Ok(value.value().clone()),
+        (Kind::List, Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(str::to_string)
+                    .context("expected an array of strings")
+            })
+            .collect::<Result<Vec<String>>>()
+            .map(|items| items.join(",")),
+        (Kind::Number, Value::Integer(value)) => Ok(value.value().to_string()),
+        (Kind::Number, Value::Float(value)) => Ok(value.value().to_string()),
+        (Kind::Text | Kind::Path, _) => bail!("expected a string"),
+        (Kind::List, _) => bail!("expected an array of strings"),
+        (Kind::Number, _) => bail!("expected a number"),
+    }
+}
+
+fn set_in_file(path: &Path, setting: &Setting, value: &str) -> Result<()> {
+    let mut document = if path.is_file() {
+        load_document(path)?
+    } else {
+        DocumentMut::new()
+    };
+    let value = value.trim();
+    if value.is_empty() {
+        document.remove(setting.key);
+    } else {
+        validate(setting, value)?;
+        let item = match setting.kind {
+            Kind::Text | Kind::Path => Value::from(value),
+            Kind::List => Value::Array(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect::<Array>(),
+            ),
+            Kind::Number => match value.parse::<i64>() {
+                Ok(number) => Value::from(number),
+                Err(_) => Value::from(value.parse::<f64>()?),
+            },
+        };
+        document[setting.key] = Item::Value(item);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    write_atomic(path, document.to_string())
+        .with_context(|| format!("Failed to write config {}", path.display()))
+}
+
+fn validate(setting: &Setting, value: &str) -> Result<()> {
+    match setting.key {
+        "project_prefix" if !value.chars().all(|ch| ch.is_ascii_alphanumeric()) => {
+            bail!("project_prefix must be letters and digits only")
+        }
+        "default_status" if value.contains(char::is_whitespace) => {
+            bail!("default_status must be a single word")
+        }
+        "status_transitions" => StatusTransitions::parse(value).map(|_| ()),
+        "agents" => {
+            for agent in value.split(',').map(str::trim) {
+                if !agent.is_empty() && !KNOWN_AGENTS.contains(&agent) {
+                    bail!(
+                        "Unknown agent {agent}; supported agents: {}",
+                        KNOWN_AGENTS.join(", ")
+                    );
+                }
+            }
+            Ok(())
+        }
+        "lock_timeout" => match value.parse::<f64>() {
+            Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(()),
+            _ => bail!("lock_timeout must be a non-negative number of seconds"),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Expand `~/` and resolve relative paths against `base`.
+pub(crate) fn resolve_path(value: &str, base: &Path) -> String {
+    let path = match (value.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(value),
+    };
+    let path = if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    };
+    crate::normalize_path(&path).display().to_string()
+}
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Nearest directory holding `.pman/`, searched from the current directory
+/// and then from the pman executable, like Notes root discovery.
+pub(crate) fn workspace_dir() -> Option<PathBuf> {
+    let starts = [std::env::current_dir().ok(), std::env::current_exe().ok()];
+    starts.into_iter().flatten().find_map(|start| {
+        start
+            .ancestors()
+            .find(|dir| dir.join(".pman").is_dir())
+            .map(Path::to_path_buf)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setting(key: &str) -> &'static Setting {
+        find_setting(key).unwrap()
+    }
+
+    #[test]
+    fn workspace_file_overrides_user_file_and_defaults() {
+        let temp = tempdir().unwrap();
+        let workspace = temp.path().join("team").join(".pman").join("config.toml");
+        let user = temp.path().join("user.toml");
+        fs::create_dir_all(workspace.parent().unwrap()).unwrap();
+        fs::write(
+            &workspace,
+            "# shared settings\ndefault_status = \"active\"\ntemplates_dir = \"Templates\"\n",
+        )
+        .unwrap();
+        fs::write(
+            &user,
+            "default_status = \"draft\"\nagents = [\"claude\"]\npager = 3\n",
+        )
+        .unwrap();
+        let files = ConfigFiles {
+            workspace: Some(workspace.clone()),
+            user: Some(user.clone()),
+        };
+
+        let entry = |key| entry(&files, setting(key)).unwrap();
+        assert_eq!(entry("default_status").value.as_deref(), Some("active"));
+        assert_eq!(
+            entry("default_status").source,
+            ConfigSource::Workspace(workspace.clone())
+        );
+        assert_eq!(
+            entry("templates_dir").value,
+            Some(
+                temp.path()
+                    .join("team")
+                    .join("Templates")
+                    .display()
+                    .to_string()
+            )
+        );
+        assert_eq!(entry("agents").value.as_deref(), Some("claude"));
+        assert_eq!(entry("agents").source, ConfigSource::User(user.clone()));
+        assert_eq!(entry("project_prefix").source, ConfigSource::Default);
+
+        let err = files.lookup(setting("pager")).unwrap_err();
+        assert!(format!("{err:#}").contains("expected a string"));
+    }
+
+    #[test]
+    fn set_validates_and_keeps_comments() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join(".pman").join("config.toml");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "# shared settings\nproject_prefix = \"ops\"\npager = \"less -R\"\n",
+        )
+        .unwrap();
+
+        set_in_file(&path, setting("agents"), "claude, codex").unwrap();
+        set_in_file(&path, setting("lock_timeout"), "2.5").unwrap();
+        set_in_file(&path, setting("pager"), "").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# shared settings\nproject_prefix = \"ops\"\nagents = [\"claude\", \"codex\"]\nlock_timeout = 2.5\n"
+        );
+
+        assert!(set_in_file(&path, setting("agents"), "cursor").is_err());
+        assert!(set_in_file(&path, setting("status_transitions"), "draft").is_err());
+        assert!(set_in_file(&path, setting("project_prefix"), "my-team").is_err());
+        assert!(find_setting("editor").is_err());
+    }
+}
@@ -0,0 +1,564 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lock::lock_notes;
+use crate::output::{render_json, render_table, render_tsv};
+use crate::resolve::project_dir_names;
+use crate::{
+    Frontmatter, NotesPaths, OutputFormat, REGISTRY_HEADER, Registry, RegistryEntry,
+    normalize_path, project_id_from_dir,
+};
+
+/// Inconsistencies found by `pman doctor`, with stable codes for scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoctorCode {
+    /// `Projects/_registry.md` does not exist.
+    MissingRegistry,
+    /// A registry row links to a note that exists in neither project folder.
+    MissingNote,
+    /// A registry row links to the wrong folder; the directory moved.
+    StaleLink,
+    /// A project directory has no registry row.
+    UnregisteredDir,
+    /// Registry status disagrees with the folder the project lives in.
+    ArchiveStatus,
+    /// Several registry rows share an id.
+    DuplicateId,
+    /// README `status:` frontmatter disagrees with the registry.
+    FrontmatterStatus,
+}
+
+impl DoctorCode {
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::MissingRegistry => "D001",
+            Self::MissingNote => "D002",
+            Self::StaleLink => "D003",
+            Self::UnregisteredDir => "D004",
+            Self::ArchiveStatus => "D005",
+            Self::DuplicateId => "D006",
+            Self::FrontmatterStatus => "D007",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MissingRegistry => "missing-registry",
+            Self::MissingNote => "missing-note",
+            Self::StaleLink => "stale-link",
+            Self::UnregisteredDir => "unregistered-dir",
+            Self::ArchiveStatus => "archive-status",
+            Self::DuplicateId => "duplicate-id",
+            Self::FrontmatterStatus => "frontmatter-status",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorIssue {
+    pub code: DoctorCode,
+    /// Registry id or project directory the issue is about.
+    pub subject: String,
+    pub message: String,
+    /// Whether `--fix` knows a safe repair for this issue.
+    pub fixable: bool,
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub issues: Vec<DoctorIssue>,
+}
+
+impl DoctorReport {
+    /// True when nothing is left to repair.
+    pub fn ok(&self) -> bool {
+        self.issues.iter().all(|issue| issue.fixed)
+    }
+
+    fn push(&mut self, code: DoctorCode, subject: &str, message: String, fixable: bool, fix: bool) {
+        self.issues.push(DoctorIssue {
+            code,
+            subject: subject.to_string(),
+            message,
+            fixable,
+            fixed: fixable && fix,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Projects,
+    Archives,
+}
+
+/// Cross-check the registry, `Projects/`, `Archives/Projects/` and README
+/// frontmatter. With `fix`, safe repairs are applied and marked as fixed.
+pub fn doctor(paths: &NotesPaths, fix: bool) -> Result<DoctorReport> {
+    let _lock = if fix {
+        Some(lock_notes(&paths.root)?)
+    } else {
+        None
+    };
+    let mut report = DoctorReport::default();
+    let registry_exists = paths.registry.exists();
+    let mut registry = if registry_exists {
+        Registry::load(&paths.registry)?
+    } else {
+        report.push(
+            DoctorCode::MissingRegistry,
+            "Projects/_registry.md",
+            "registry file is missing".to_string(),
+            true,
+            fix,
+        );
+        Registry::parse(REGISTRY_HEADER)
+    };
+    let mut registry_changed = !registry_exists;
+
+    let active_dirs = project_dir_names(&paths.projects_dir)?;
+    let archived_dirs = project_dir_names(&paths.archives_projects_dir)?;
+
+    let mut id_counts = HashMap::new();
+    for entry in registry.entries() {
+        *id_counts
+            .entry(entry.id.to_ascii_uppercase())
+            .or_insert(0usize) += 1;
+    }
+    let mut duplicates = id_counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .collect::<Vec<(String, usize)>>();
+    duplicates.sort();
+    for (id, count) in duplicates {
+        report.push(
+            DoctorCode::DuplicateId,
+            &id,
+            format!("{count} registry rows share this id"),
+            false,
+            fix,
+        );
+    }
+
+    for entry in registry.entries_mut() {
+        let linked = normalize_path(&paths.projects_dir.join(entry.note_path()));
+        let dir_name = entry.dir_name().unwrap_or_default().to_string();
+        let location = if active_dirs.contains(&dir_name) {
+            Some(Location::Projects)
+        } else if archived_dirs.contains(&dir_name) {
+            Some(Location::Archives)
+        } else {
+            None
+        };
+
+        let Some(location) = location else {
+            // Rows may link to loose notes rather than project directories.
+            if linked.exists() {
+                continue;
+            }
+            report.push(
+                DoctorCode::MissingNote,
+                &entry.id,
+                format!("note {} does not exist", entry.note_path()),
+                false,
+                fix,
+            );
+            continue;
+        };
+
+        let dir = match location {
+            Location::Projects => paths.projects_dir.join(&dir_name),
+            Location::Archives => paths.archives_projects_dir.join(&dir_name),
+        };
+        let readme = dir.join("README.md");
+        if !linked.starts_with(normalize_path(&dir)) || !linked.exists() {
+            let note_path = note_path_for(location, &dir_name, readme.exists());
+            report.push(
+                DoctorCode::StaleLink,
+                &entry.id,
+                format!(
+                    "links to {} but the note is at {note_path}",
+                    entry.note_path()
+                ),
+                true,
+                fix,
+            );
+            if fix {
+                entry.note_link = RegistryEntry::link_for(&note_path);
+                registry_changed = true;
+            }
+        }
+
+        let note_status = read_frontmatter(&readme).and_then(|fm| fm.get("status"));
+        let archived = entry.status.eq_ignore_ascii_case("archived");
+        match location {
+            Location::Archives if !archived => {
+                report.push(
+                    DoctorCode::ArchiveStatus,
+                    &entry.id,
+                    format!(
+                        "status is {} but the project is in Archives/Projects",
+                        entry.status
+                    ),
+                    true,
+                    fix,
+                );
+                if fix {
+                    entry.status = "archived".to_string();
+                    registry_changed = true;
+                }
+            }
+            Location::Projects if archived => {
+                report.push(
+                    DoctorCode::ArchiveStatus,
+                    &entry.id,
+                    "status is archived but the project is in Projects".to_string(),
+                    true,
+                    fix,
+                );
+                if fix {
+                    entry.status = note_status
+                        .clone()
+                        .filter(|status| !status.eq_ignore_ascii_case("archived"))
+                        .unwrap_or_else(|| "active".to_string());
+                    registry_changed = true;
+                }
+            }
+            _ => {}
+        }
+
+        // Archiving leaves the README untouched, so only active projects are compared.
+        let effective_status = entry.status.clone();
+        if location == Location::Projects
+            && readme.exists()
+            && !effective_status.eq_ignore_ascii_case("archived")
+            && !note_status
+                .as_deref()
+                .is_some_and(|status| status.eq_ignore_ascii_case(&effective_status))
+        {
+            report.push(
+                DoctorCode::FrontmatterStatus,
+                &entry.id,
+                // Either side may be the one that is right, so this is left to
+                // `pman status` or a README edit.
+                format!(
+                    "README status is {} but the registry says {effective_status}",
+                    note_status.as_deref().unwrap_or("missing")
+                ),
+                false,
+                fix,
+            );
+        }
+    }
+
+    for (location, dirs) in [
+        (Location::Projects, &active_dirs),
+        (Location::Archives, &archived_dirs),
+    ] {
+        for dir_name in dirs {
+            if registry.find_by_dir_name(dir_name).is_some() {
+                continue;
+            }
+            let entry = unregistered_entry(paths, location, dir_name);
+            let (fixable, message) = match &entry {
+                Some(entry) if registry.find(&entry.id).is_some() => (
+                    false,
+                    format!(
+                        "directory has no registry row and its id {} is already taken",
+                        entry.id
+                    ),
+                ),
+                Some(entry) => (
+                    true,
+                    format!("directory has no registry row (would add {})", entry.id),
+                ),
+                None => (
+                    false,
+                    "directory has no registry row and no project id could be derived".to_string(),
+                ),
+            };
+            report.push(DoctorCode::UnregisteredDir, dir_name, message, fixable, fix);
+            if fix
+                && fixable
+                && let Some(entry) = entry
+            {
+                registry.push(entry);
+                registry_changed = true;
+            }
+        }
+    }
+
+    if fix && registry_changed {
+        fs::create_dir_all(&paths.projects_dir).with_context(|| {
+            format!(
+                "Failed to create projects directory {}",
+                paths.projects_dir.display()
+            )
+        })?;
+        registry.save(&paths.registry)?;
+    }
+
+    Ok(report)
+}
+
+/// Render a doctor report; `None` prints one line per issue and a summary.
+pub fn render_doctor(report: &DoctorReport, format: Option<OutputFormat>) -> String {
+    let state = |issue: &DoctorIssue| {
+        if issue.fixed {
+            "fixed"
+        } else if issue.fixable {
+            "fixable"
+        } else {
+            "manual"
+        }
+        .to_string()
+    };
+    let rows = report
+        .issues
+        .iter()
+        .map(|issue| {
+            vec![
+                issue.code.code().to_string(),
+                issue.code.name().to_string(),
+                issue.subject.clone(),
+                state(issue),
+                issue.message.clone(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    match format {
+        None => {
+            if report.issues.is_empty() {
+                return "No issues found.\n".to_string();
+            }
+            let mut output = String::new();
+            for issue in &report.issues {
+                output.push_str(&format!(
+                    "{} {} {}: {} [{}]\n",
+                    issue.code.code(),
+                    issue.code.name(),
+                    issue.subject,
+                    issue.message,
+                    state(issue)
+                ));
+            }
+            let fixed = report.issues.iter().filter(|issue| issue.fixed).count();
+            let fixable = report
+                .issues
+                .iter()
+                .filter(|issue| issue.fixable && !issue.fixed)
+                .count();
+            output.push_str(&format!("\n{} issue(s) found", report.issues.len()));
+            if fixed > 0 {
+                output.push_str(&format!(", {fixed} fixed"));
+            }
+            if fixable > 0 {
+                output.push_str(&format!(", {fixable} fixable with --fix"));
+            }
+            output.push_str(".\n");
+            output
+        }
+        Some(OutputFormat::Json) => render_json(&json!({
+            "ok": report.ok(),
+            "issues": report
+                .issues
+                .iter()
+                .map(|issue| json!({
+                    "code": issue.code.code(),
+                    "name": issue.code.name(),
+                    "subject": issue.subject,
+                    "message": issue.message,
+                    "fixable": issue.fixable,
+                    "fixed": issue.fixed,
+                }))
+                .collect::<Vec<Value>>(),
+        })),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => {
+            render_table(&["code", "name", "subject", "state", "message"], &rows)
+        }
+    }
+}
+
+fn note_path_for(location: Location, dir_name: &str, has_readme: bool) -> String {
+    let base = match location {
+        Location::Projects => format!("{dir_name}/"),
+        Location::Archives => format!("../Archives/Projects/{dir_name}/"),
+    };
+    if has_readme {
+        format!("{base}README.md")
+    } else {
+        base
+    }
+}
+
+fn read_frontmatter(readme: &Path) -> Option<Frontmatter> {
+    fs::read_to_string(readme)
+        .ok()
+        .map(|content| Frontmatter::parse(&content))
+}
+
+/// Rebuild a registry row from a project README (`# ID: Name`, `**Created**:`
+/// and `status:`), falling back to the directory name.
+fn unregistered_entry(
+    paths: &NotesPaths,
+    location: Location,
+    dir_name: &str,
+) -> Option<RegistryEntry> {
+    let dir = match location {
+        Location::Projects => paths.projects_dir.join(dir_name),
+        Location::Archives => paths.archives_projects_dir.join(dir_name),
+    };
+    let readme: PathBuf = dir.join("README.md");
+    let content = fs::read_to_string(&readme).unwrap_or_default();
+
+    let heading = content
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .and_then(|heading| heading.split_once(": "))
+        .filter(|(id, _)| {
+            !id.is_empty() && id.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+        });
+    let (id, name) = match heading {
+        Some((id, name)) => (id.to_string(), name.trim().to_string()),
+        None => (project_id_from_dir(dir_name).ok()?, dir_name.to_string()),
+    };
+    let created = content
+        .lines()
+        .find_map(|line| line.strip_prefix("**Created**:"))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+    let status = match location {
+        Location::Archives => "archived".to_string(),
+        Location::Projects => Frontmatter::parse(&content)
+            .get("status")
+            .filter(|status| !status.is_empty())
+            .unwrap_or_else(|| "active".to_string()),
+    };
+
+    Some(RegistryEntry::new(
+        &id,
+        &name,
+        &status,
+        &created,
+        &note_path_for(location, dir_name, readme.exists()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, NotesPaths) {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::create_dir_all(&paths.archives_projects_dir).unwrap();
+        (temp, paths)
+    }
+
+    fn write_readme(dir: &Path, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("README.md"), content).unwrap();
+    }
+
+    #[test]
+    fn reports_and_fixes_registry_drift() {
+        let (_temp, paths) = setup();
+        // Moved to Archives by hand, registry still active and linked to Projects.
+        write_readme(
+            &paths.archives_projects_dir.join("proj-1-moved"),
+            "---\nstatus: active\n---\n\n# PROJ-1: Moved\n",
+        );
+        // Registry and README disagree.
+        write_readme(
+            &paths.projects_dir.join("proj-2-drift"),
+            "---\nstatus: draft\n---\n\n# PROJ-2: Drift\n",
+        );
+        // Created by hand without a registry row.
+        write_readme(
+            &paths.projects_dir.join("proj-4-manual"),
+            "---\nstatus: review\n---\n\n# PROJ-4: Manual Work\n\n**Created**: 2026-03-01\n",
+        );
+        fs::write(
+            &paths.registry,
+            format!(
+                "{REGISTRY_HEADER}| PROJ-1 | Moved | active | 2026-01-01 | [proj-1-moved/README.md](proj-1-moved/README.md) |\n| PROJ-2 | Drift | active | 2026-01-01 | [proj-2-drift/README.md](proj-2-drift/README.md) |\n| PROJ-3 | Gone | active | 2026-01-01 | [proj-3-gone/README.md](proj-3-gone/README.md) |\n| PROJ-3 | Twin | active | 2026-01-01 | [proj-3-gone/README.md](proj-3-gone/README.md) |\n"
+            ),
+        )
+        .unwrap();
+
+        let report = doctor(&paths, false).unwrap();
+        let codes = report
+            .issues
+            .iter()
+            .map(|issue| (issue.code.code(), issue.subject.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                ("D006", "PROJ-3"),
+                ("D003", "PROJ-1"),
+                ("D005", "PROJ-1"),
+                ("D007", "PROJ-2"),
+                ("D002", "PROJ-3"),
+                ("D002", "PROJ-3"),
+                ("D004", "proj-4-manual"),
+            ]
+        );
+        assert!(!report.ok());
+        assert!(render_doctor(&report, None).contains("3 fixable with --fix"));
+
+        let report = doctor(&paths, true).unwrap();
+        assert_eq!(report.issues.iter().filter(|issue| issue.fixed).count(), 3);
+
+        let registry = Registry::load(&paths.registry).unwrap();
+        let moved = registry.find("PROJ-1").unwrap();
+        assert_eq!(moved.status, "archived");
+        assert_eq!(
+            moved.note_path(),
+            "../Archives/Projects/proj-1-moved/README.md"
+        );
+        let manual = registry.find("PROJ-4").unwrap();
+        assert_eq!(manual.name, "Manual Work");
+        assert_eq!(manual.status, "review");
+        assert_eq!(manual.created, "2026-03-01");
+        let drift = fs::read_to_string(paths.projects_dir.join("proj-2-drift/README.md")).unwrap();
+        assert!(drift.starts_with("---\nstatus: draft\n---"));
+
+        let remaining = doctor(&paths, false).unwrap();
+        assert!(remaining.issues.iter().all(|issue| matches!(
+            issue.code,
+            DoctorCode::MissingNote | DoctorCode::DuplicateId | DoctorCode::FrontmatterStatus
+        )));
+    }
+
+    #[test]
+    fn recreates_missing_registry_from_directories() {
+        let (_temp, paths) = setup();
+        write_readme(
+            &paths.projects_dir.join("proj-7-solo"),
+            "---\nstatus: active\n---\n\n# PROJ-7: Solo\n",
+        );
+        // Underscore directories are not projects, as in project lookup.
+        fs::create_dir_all(paths.projects_dir.join("_assets")).unwrap();
+
+        let report = doctor(&paths, true).unwrap();
+        assert!(report.ok());
+        assert_eq!(report.issues[0].code, DoctorCode::MissingRegistry);
+        let registry = Registry::load(&paths.registry).unwrap();
+        assert_eq!(
+            registry.find("PROJ-7").unwrap().note_path(),
+            "proj-7-solo/README.md"
+        );
+        assert!(doctor(&paths, false).unwrap().issues.is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use anyhow::{Error, Result, bail};
+use serde_json::{Map, Value, json};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::OutputFormat;
+use crate::output::{render_json, render_table, render_tsv};
+
+const FENCE: &str = "---";
+
+/// The `---` frontmatter block of a note plus the body that follows it.
+///
+/// Only flat `key: value` lines are interpreted. Comments, nested values and
+/// anything else inside the block is kept verbatim so edits round-trip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frontmatter {
+    /// Lines between the opening and closing fences.
+    lines: Vec<String>,
+    /// Whether the note had a frontmatter block when parsed.
+    has_block: bool,
+    /// Everything after the closing fence (the whole note when there is no block).
+    body: String,
+    /// The note's line ending, reused for the fences and block lines.
+    newline: &'static str,
+}
+
+/// A `key=value` filter matched against frontmatter values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaFilter {
+    pub key: String,
+    pub value: String,
+}
+
+impl Frontmatter {
+    pub fn parse(content: &str) -> Self {
+        let Some(rest) = content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))
+        else {
+            return Self::without_block(content);
+        };
+
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        while offset < rest.len() {
+            let end = rest[offset..]
+                .find('\n')
+                .map(|index| offset + index + 1)
+                .unwrap_or(rest.len());
+            let line = rest[offset..end].trim_end_matches(['\n', '\r']);
+            if line == FENCE {
+                return Self {
+                    lines,
+                    has_block: true,
+                    body: rest[end..].to_string(),
+                    newline: line_ending(content),
+                };
+            }
+            lines.push(line.to_string());
+            offset = end;
+        }
+
+        // An unterminated block is body text, not frontmatter.
+        Self::without_block(content)
+    }
+
+    fn without_block(content: &str) -> Self {
+        Self {
+            lines: Vec::new(),
+            has_block: false,
+            body: content.to_string(),
+            newline: line_ending(content),
+        }
+    }
+
+    pub fn has_block(&self) -> bool {
+        self.has_block
+    }
+
+    /// The value of `key` with surrounding quotes removed.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.lines
+            .iter()
+            .find_map(|line| split_key_value(line).filter(|(name, _)| *name == key))
+            .map(|(_, value)| unquote(value))
+    }
+
+    /// All flat `key: value` pairs in file order.
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.lines
+            .iter()
+            .filter_map(|line| split_key_value(line))
+            .map(|(key, value)| (key.to_string(), unquote(value)))
+            .collect()
+    }
+
+    /// Set `key`, replacing it in place (with any nested lines) or appending it.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let line = format!("{key}: {}", quote(value));
+        match self.position(key) {
+            Some(index) => {
+                let nested = self.nested_len(index);
+                self.lines.splice(index..=index + nested, [line]);
+            }
+            None => self.lines.push(line),
+        }
+        self.has_block = true;
+    }
+
+    /// Like [`Frontmatter::set`], but a missing key goes first in the block.
+    pub fn set_first(&mut self, key: &str, value: &str) {
+        if self.position(key).is_some() {
+            self.set(key, value);
+        } else {
+            self.lines.insert(0, format!("{key}: {}", quote(value)));
+            self.has_block = true;
+        }
+    }
+
+    /// Whether `key` is present and equal to `value` (case-insensitive).
+    pub fn matches(&self, filter: &MetaFilter) -> bool {
+        self.get(&filter.key)
+            .is_some_and(|value| value.eq_ignore_ascii_case(&filter.value))
+    }
+
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.entries()
+                .into_iter()
+                .map(|(key, value)| (key, Value::String(value)))
+                .collect::<Map<String, Value>>(),
+        )
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| split_key_value(line).is_some_and(|(name, _)| name == key))
+    }
+
+    /// Number of indented lines (nested maps, block lists) following `index`.
+    fn nested_len(&self, index: usize) -> usize {
+        self.lines[index + 1..]
+            .iter()
+            .take_while(|line| line.starts_with([' ', '\t']))
+            .count()
+    }
+}
+
+impl fmt::Display for Frontmatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.has_block {
+            return f.write_str(&self.body);
+        }
+        let newline = self.newline;
+        write!(f, "{FENCE}{newline}")?;
+        for line in &self.lines {
+            write!(f, "{line}{newline}")?;
+        }
+        write!(f, "{FENCE}{newline}")?;
+        f.write_str(&self.body)
+    }
+}
+
+impl FromStr for MetaFilter {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((key, wanted)) = value.split_once('=') else {
+            bail!("Invalid filter {value}; expected key=value");
+        };
+        let key = key.trim();
+        validate_key(key)?;
+        Ok(Self {
+            key: key.to_string(),
+            value: wanted.trim().to_string(),
+        })
+    }
+}
+
+/// Render frontmatter for `pman meta get`.
+///
+/// With a key, `None` prints the bare value; without one it prints
+/// `key: value` lines.
+pub fn render_meta(
+    frontmatter: &Frontmatter,
+    key: Option<&str>,
+    format: Option<OutputFormat>,
+) -> Result<String> {
+    let entries = match key {
+        Some(key) => {
+            let Some(value) = frontmatter.get(key) else {
+                bail!("Frontmatter key not found: {key}");
+            };
+            vec![(key.to_string(), value)]
+        }
+        None => frontmatter.entries(),
+    };
+
+    let rows = entries
+        .iter()
+        .map(|(key, value)| vec![key.clone(), value.clone()])
+        .collect::<Vec<Vec<String>>>();
+    Ok(match format {
+        None if key.is_some() => format!("{}\n", entries[0].1),
+        None => entries
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}\n"))
+            .collect(),
+        Some(OutputFormat::Json) if key.is_some() => {
+            render_json(&json!({ "key": entries[0].0, "value": entries[0].1 }))
+        }
+        Some(OutputFormat::Json) => render_json(&frontmatter.to_json()),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => render_table(&["key", "value"], &rows),
+    })
+}
+
+/// Frontmatter keys are restricted to simple identifiers so they stay greppable.
+pub(crate) fn validate_key(key: &str) -> Result<()> {
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        bail!("Invalid frontmatter key {key:?}; use letters, digits, '-' or '_'");
+    }
+    Ok(())
+}
+
+/// Insert a blank line between a newly created block and the note body.
+pub(crate) fn with_block(content: &str) -> Frontmatter {
+    let mut frontmatter = Frontmatter::parse(content);
+    if !frontmatter.has_block && !frontmatter.body.is_empty() {
+        frontmatter.body.insert_str(0, frontmatter.newline);
+    }
+    frontmatter
+}
+
+/// `\r\n` when the first line of `content` ends with it, otherwise `\n`.
+fn line_ending(content: &str) -> &'static str {
+    match content.find('\n') {
+        Some(index) if content[..index].ends_with('\r') => "\r\n",
+        _ => "\n",
+    }
+}
+
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    if line.starts_with([' ', '\t', '#', '-']) {
+        return None;
+    }
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim_end();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((key, value.trim()))
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return value[1..value.len() - 1]
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\");
+    }
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    value.to_string()
+}
+
+fn quote(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value != value.trim()
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.starts_with([
+            '[', ']', '{', '}', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`', '#', '-', '?',
+        ]);
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flat_keys_and_keeps_other_lines() {
+        let content =
+            "---\nstatus: active\n# owner of record\nowner: \"Ada L\"\ntags:\n  - a\n---\n# T\n";
+        let frontmatter = Frontmatter::parse(content);
+        assert!(frontmatter.has_block());
+        assert_eq!(frontmatter.get("owner").as_deref(), Some("Ada L"));
+        assert_eq!(frontmatter.get("tags").as_deref(), Some(""));
+        assert_eq!(frontmatter.get("missing"), None);
+        assert_eq!(frontmatter.to_string(), content);
+
+        let plain = Frontmatter::parse("# T\n---\n");
+        assert!(!plain.has_block());
+        assert_eq!(plain.to_string(), "# T\n---\n");
+    }
+
+    #[test]
+    fn set_replaces_in_place_or_appends() {
+        let mut frontmatter = Frontmatter::parse("---\nstatus: active\ntags:\n  - a\n---\n# T\n");
+        frontmatter.set("tags", "payments");
+        frontmatter.set("ticket", "PAY-12: retries");
+        assert_eq!(
+            frontmatter.to_string(),
+            "---\nstatus: active\ntags: payments\nticket: \"PAY-12: retries\"\n---\n# T\n"
+        );
+        assert_eq!(
+            frontmatter.get("ticket").as_deref(),
+            Some("PAY-12: retries")
+        );
+
+        let mut frontmatter = with_block("# T\n");
+        frontmatter.set("owner", "ada");
+        assert_eq!(frontmatter.to_string(), "---\nowner: ada\n---\n\n# T\n");
+
+        let mut frontmatter = Frontmatter::parse("---\r\nstatus: draft\r\n---\r\n# T\r\n");
+        frontmatter.set("status", "active");
+        frontmatter.set("updated", "2026-03-14");
+        assert_eq!(
+            frontmatter.to_string(),
+            "---\r\nstatus: active\r\nupdated: 2026-03-14\r\n---\r\n# T\r\n"
+        );
+        let mut frontmatter = with_block("# T\r\n");
+        frontmatter.set("owner", "ada");
+        assert_eq!(
+            frontmatter.to_string(),
+            "---\r\nowner: ada\r\n---\r\n\r\n# T\r\n"
+        );
+    }
+
+    #[test]
+    fn filters_parse_and_match_case_insensitively() {
+        let filter = "area = Payments".parse::<MetaFilter>().unwrap();
+        assert_eq!(filter.key, "area");
+        assert!(Frontmatter::parse("---\narea: payments\n---\n").matches(&filter));
+        assert!(!Frontmatter::parse("# none\n").matches(&filter));
+        assert!("area".parse::<MetaFilter>().is_err());
+        assert!("bad key=x".parse::<MetaFilter>().is_err());
+    }
+}
@@ -1,11 +1,58 @@
 use anyhow::{Context, Result, bail};
 use chrono::Local;
-use regex::Regex;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde_json::{Value, json};
 use std::fs;
-use std::fs::OpenOptions;
-use std::io::{IsTerminal, Write};
-use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::io::IsTerminal;
+use std::path::{Component, Path, PathBuf};
+
+mod adr;
+mod atomic;
+mod attach;
+mod config;
+mod doctor;
+mod frontmatter;
+mod locate;
+mod lock;
+mod mcp;
+mod open;
+mod output;
+mod patch;
+mod registry;
+mod resolve;
+mod revision;
+mod search;
+mod section;
+mod show;
+mod template;
+mod vault;
+
+pub use adr::{Adr, DEFAULT_ADR_STATUS, list_adrs, new_adr, render_adrs};
+pub use attach::{add_project_note, attach_file};
+pub use config::{ConfigEntry, ConfigSource, config_get, config_list, config_set, render_config};
+pub use doctor::{DoctorCode, DoctorIssue, DoctorReport, doctor, render_doctor};
+pub use frontmatter::{Frontmatter, MetaFilter, render_meta};
+pub use locate::{
+    AgentBridge, NotesRootResolution, RootCandidate, WhereReport, render_where, resolve_notes_root,
+    set_verbose, where_report,
+};
+pub use mcp::McpServer;
+pub use open::{open_note, resolve_open_target};
+pub use output::OutputFormat;
+pub use patch::{DEFAULT_PATCH_FUZZ, HunkOutcome, PatchReport, patch_note, render_patch};
+pub use registry::{Registry, RegistryEntry};
+pub use resolve::{ResolvedProject, project_note_path, resolve_project};
+pub use revision::{RevisionMismatch, note_revision};
+pub use search::{SearchMatch, SearchOptions, render_search, search_notes};
+pub use section::{Heading, render_outline};
+pub use show::{ProjectSummary, render_show, show_project};
+pub use template::{ProjectTemplate, TemplateVar};
+pub use vault::{Vault, active_vault, render_vaults, select_vault, vaults};
+
+use atomic::write_atomic;
+use lock::lock_notes;
+use output::{render_json, render_table, render_tsv};
+use revision::check_revision;
 
 const REGISTRY_HEADER: &str = "# Project Registry\n\nFlat list of project notes. IDs are chronological and unique across all projects.\n\n| ID | Name | Status | Created | Note |\n| --- | --- | --- | --- | --- |\n";
 
@@ -15,8 +62,12 @@ const PROJECT_SKILL: &str = include_str!("../resources/skills/project/SKILL.md")
 const NOTES_DIR_ENV_VAR: &str = "PMAN_NOTES_DIR";
 const PROJECT_PREFIX_ENV_VAR: &str = "PMAN_PROJECT_PREFIX";
 const DEFAULT_PROJECT_PREFIX: &str = "proj";
+const DEFAULT_PROJECT_STATUS: &str = "draft";
 const FORCE_CLAUDE_PRESENT_ENV_VAR: &str = "PMAN_FORCE_CLAUDE_PRESENT";
 const FORCE_CODEX_PRESENT_ENV_VAR: &str = "PMAN_FORCE_CODEX_PRESENT";
+const STATUS_TRANSITIONS_ENV_VAR: &str = "PMAN_STATUS_TRANSITIONS";
+const DEFAULT_STATUS_TRANSITIONS: &str =
+    "draft>active,active>review,review>active,review>done,done>active";
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WcFlags {
@@ -26,6 +77,73 @@ pub struct WcFlags {
     pub chars: bool,
 }
 
+/// Matching rules for `replace_in_note` (`pman edit --find/--replace`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplaceOptions {
+    /// Treat the find text as a regex; `$1`/`${name}` expand in the replacement.
+    pub regex: bool,
+    /// Replace every occurrence instead of only the first.
+    pub all: bool,
+    /// Refuse to edit unless the note has exactly this many matches.
+    pub expect_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WcCounts {
+    pub path: PathBuf,
+    pub lines: usize,
+    pub words: usize,
+    pub bytes: usize,
+    pub chars: usize,
+}
+
+/// A registry row with its note path resolved against the Notes root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRecord {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub created: String,
+    /// Note link target as written in the registry (relative to `Projects/`).
+    pub note: String,
+    /// The registry's Note cell as written, printed by the default `list` output.
+    pub note_cell: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyCheck {
+    pub name: String,
+    pub ok: bool,
+    pub problem: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub workspace: PathBuf,
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl VerifyReport {
+    pub fn ok(&self) -> bool {
+        self.checks.iter().all(|check| check.ok)
+    }
+}
+
+/// Allowed project status transitions, parsed from `from>to` pairs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTransitions {
+    edges: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusChange {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub note: PathBuf,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct LineRange {
     start: usize,
@@ -55,56 +173,92 @@ impl NotesPaths {
 }
 
 pub fn resolve_notes_dir(notes_dir: Option<PathBuf>) -> Result<PathBuf> {
-    if let Some(path) = notes_dir {
-        return Ok(path);
-    }
-
-    if let Some(path) = std::env::var_os(NOTES_DIR_ENV_VAR) {
-        let path = PathBuf::from(path);
-        if !path.as_os_str().is_empty() {
-            return Ok(path);
-        }
-    }
-
-    // Default to ~/Notes
-    if let Some(home) = std::env::var_os("HOME") {
-        let default_notes = PathBuf::from(home).join("Notes");
-        if default_notes.exists() {
-            return Ok(default_notes);
-        }
-    }
-
-    if let Some(path) = find_notes_root_from_path(&std::env::current_exe()?) {
-        return Ok(path);
-    }
-
-    if let Ok(current_dir) = std::env::current_dir() {
-        if let Some(path) = find_notes_root_from_path(&current_dir) {
-            return Ok(path);
-        }
-    }
+    let resolution = locate::resolve_notes_root(notes_dir)?;
+    locate::trace_if_verbose(&resolution);
+    resolution.root.with_context(|| {
+        format!(
+            "Could not locate Notes root; set {NOTES_DIR_ENV_VAR}, set notes_dir in .pman/config.toml, or use --notes-dir to specify it (run 'pman where' to see what was tried)"
+        )
+    })
+}
 
-    bail!("Could not locate Notes root; set {NOTES_DIR_ENV_VAR} or use --notes-dir to specify it")
+/// Status for new projects: `default_status` from config, or `draft`.
+pub fn default_project_status() -> Result<String> {
+    Ok(config::setting("default_status")?.unwrap_or_else(|| DEFAULT_PROJECT_STATUS.to_string()))
 }
 
-fn project_dir_prefix() -> String {
-    let Some(value) = std::env::var_os(PROJECT_PREFIX_ENV_VAR) else {
-        return DEFAULT_PROJECT_PREFIX.to_string();
+fn project_dir_prefix() -> Result<String> {
+    let Some(value) = config::setting("project_prefix")? else {
+        return Ok(DEFAULT_PROJECT_PREFIX.to_string());
     };
-    let value = value.to_string_lossy().trim().to_ascii_lowercase();
+    let value = value.trim().to_ascii_lowercase();
     if value.is_empty() || !value.chars().all(|ch| ch.is_ascii_alphanumeric()) {
-        return DEFAULT_PROJECT_PREFIX.to_string();
+        return Ok(DEFAULT_PROJECT_PREFIX.to_string());
     }
-    value
+    Ok(value)
 }
 
+/// Read a note, optionally limited to a line range or a heading section
+/// (`## Next`, trailing blank lines dropped).
 pub fn read_note(
     notes_dir: Option<PathBuf>,
     path: &Path,
     lines: Option<&str>,
+    section: Option<&str>,
+    numbered: bool,
+) -> Result<String> {
+    let content = read_note_content(notes_dir, path)?;
+    render_note(&content, lines, section, numbered)
+}
+
+/// Like [`read_note`], plus the revision of the whole note for `--if-match`.
+pub fn read_note_with_revision(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    lines: Option<&str>,
+    section: Option<&str>,
+    numbered: bool,
+) -> Result<(String, String)> {
+    let content = read_note_content(notes_dir, path)?;
+    Ok((
+        render_note(&content, lines, section, numbered)?,
+        note_revision(&content),
+    ))
+}
+
+/// JSON for `pman read --format json` and `notes_read` with `revision`.
+pub fn render_note_json(path: &Path, content: &str, revision: &str) -> String {
+    render_json(&json!({
+        "path": path,
+        "revision": revision,
+        "content": content,
+    }))
+}
+
+/// Markdown headings of a note, for `pman read --outline`.
+pub fn note_outline(notes_dir: Option<PathBuf>, path: &Path) -> Result<Vec<Heading>> {
+    let (lines, _) = read_note_lines(notes_dir, path)?;
+    Ok(section::headings(&lines))
+}
+
+fn render_note(
+    content: &str,
+    lines: Option<&str>,
+    section: Option<&str>,
     numbered: bool,
 ) -> Result<String> {
-    let (all_lines, trailing_newline) = read_note_lines(notes_dir, path)?;
+    let (all_lines, trailing_newline) = split_lines(content);
+    let section_lines;
+    let lines = match (lines, section) {
+        (Some(_), Some(_)) => bail!("Use either a line range or a section, not both"),
+        (None, Some(section)) => {
+            let (_, range) = section::find_section(&all_lines, section)?;
+            let end = section::section_insert_point(&all_lines, &range);
+            section_lines = format!("{}:{}", range.start + 1, end.max(range.start + 1));
+            Some(section_lines.as_str())
+        }
+        (lines, None) => lines,
+    };
 
     let (range, selected_has_trailing_newline) = match lines {
         Some(spec) => {
@@ -160,10 +314,23 @@ pub fn write_note(
     path: &Path,
     content: &str,
     create_dirs: bool,
+    if_match: Option<&str>,
 ) -> Result<PathBuf> {
     let root = canonical_notes_root(notes_dir)?;
+    let _lock = lock_notes(&root)?;
     let target = resolve_writable_note_file(&root, path, create_dirs)?;
-    fs::write(&target, content)
+    if let Some(expected) = if_match {
+        let current = match fs::read(&target) {
+            Ok(bytes) => Some(bytes),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read note {}", target.display()));
+            }
+        };
+        check_revision(&target, expected, current.as_deref())?;
+    }
+    write_atomic(&target, content)
         .with_context(|| format!("Failed to write note {}", target.display()))?;
     Ok(target)
 }
@@ -174,11 +341,16 @@ pub fn edit_note(
     replace_lines: &str,
     with_text: &str,
     expect: Option<&str>,
+    if_match: Option<&str>,
 ) -> Result<PathBuf> {
     let root = canonical_notes_root(notes_dir)?;
+    let _lock = lock_notes(&root)?;
     let target = resolve_existing_note_file(&root, path)?;
     let content = fs::read_to_string(&target)
         .with_context(|| format!("Failed to read note {}", target.display()))?;
+    if let Some(expected) = if_match {
+        check_revision(&target, expected, Some(content.as_bytes()))?;
+    }
     let range = parse_line_range(replace_lines)?;
 
     let (mut lines, mut trailing_newline) = split_lines(&content);
@@ -192,14 +364,14 @@ pub fn edit_note(
         render_line_range(&lines, range, has_trailing)
     };
 
-    if let Some(expected) = expect {
-        if current != expected {
-            bail!(
-                "Expected text mismatch for lines {}:{}",
-                range.start,
-                range.end
-            );
-        }
+    if let Some(expected) = expect
+        && current != expected
+    {
+        bail!(
+            "Expected text mismatch for lines {}:{}",
+            range.start,
+            range.end
+        );
     }
 
     let (replacement_lines, replacement_trailing) = split_lines(with_text);
@@ -215,13 +387,182 @@ pub fn edit_note(
     }
 
     let updated = join_lines(&lines, trailing_newline);
-    fs::write(&target, updated)
+    write_atomic(&target, updated)
+        .with_context(|| format!("Failed to write note {}", target.display()))?;
+    Ok(target)
+}
+
+/// Replace text in a note by match instead of by line range. Returns the note
+/// path and the number of replacements made.
+pub fn replace_in_note(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    find: &str,
+    replacement: &str,
+    options: ReplaceOptions,
+    if_match: Option<&str>,
+) -> Result<(PathBuf, usize)> {
+    if find.is_empty() {
+        bail!("Find text must not be empty");
+    }
+    let pattern = if options.regex {
+        // `^` and `$` anchor at line boundaries, including CRLF ones.
+        RegexBuilder::new(find)
+            .multi_line(true)
+            .crlf(true)
+            .build()
+            .with_context(|| format!("Invalid find pattern {find}"))?
+    } else {
+        Regex::new(&regex::escape(find)).context("Failed to build find pattern")?
+    };
+
+    let mut replaced = 0;
+    let target = update_note(notes_dir, path, if_match, |content| {
+        let found = pattern.find_iter(content).count();
+        if let Some(expected) = options.expect_count
+            && found != expected
+        {
+            bail!(
+                "Expected {expected} match(es) for {find:?} in {}, found {found}; note left unchanged",
+                path.display()
+            );
+        }
+        if found == 0 {
+            bail!("No match for {find:?} in {}", path.display());
+        }
+
+        replaced = if options.all { found } else { 1 };
+        let limit = if options.all { 0 } else { 1 };
+        Ok(if options.regex {
+            pattern.replacen(content, limit, replacement).into_owned()
+        } else {
+            pattern
+                .replacen(content, limit, NoExpand(replacement))
+                .into_owned()
+        })
+    })?;
+    Ok((target, replaced))
+}
+
+/// Append lines to the end of a note, or to the end of a heading section
+/// (`## Notes`) when `section` is given.
+///
+/// Section text lands after the section's last non-blank line; an empty `- `
+/// placeholder bullet left by the project template is replaced.
+pub fn append_note(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    content: &str,
+    section: Option<&str>,
+    if_match: Option<&str>,
+) -> Result<PathBuf> {
+    if content.is_empty() {
+        bail!("Nothing to append; content is empty");
+    }
+    update_note(notes_dir, path, if_match, |current| {
+        let (lines, _) = split_lines(current);
+        let Some(section) = section else {
+            return Ok(insert_lines(current, lines.len()..lines.len(), content));
+        };
+        let (heading, range) = section::find_section(&lines, section)?;
+        let at = section::section_insert_point(&lines, &range);
+        let placeholder = at == heading.line + 1 && lines[heading.line].trim() == "-";
+        let replace = if placeholder { at - 1..at } else { at..at };
+        Ok(insert_lines(current, replace, content))
+    })
+}
+
+/// Insert lines after 1-based line `after_line` (0 inserts at the top).
+pub fn insert_note(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    after_line: usize,
+    content: &str,
+    if_match: Option<&str>,
+) -> Result<PathBuf> {
+    if content.is_empty() {
+        bail!("Nothing to insert; content is empty");
+    }
+    update_note(notes_dir, path, if_match, |current| {
+        let (lines, _) = split_lines(current);
+        if after_line > lines.len() {
+            bail!(
+                "Line {after_line} is out of bounds for a {}-line file",
+                lines.len()
+            );
+        }
+        Ok(insert_lines(current, after_line..after_line, content))
+    })
+}
+
+/// Replace the lines in `range` with the lines of `text`; text added at the
+/// end of the note always ends with a newline.
+fn insert_lines(content: &str, range: std::ops::Range<usize>, text: &str) -> String {
+    let (mut lines, mut trailing_newline) = split_lines(content);
+    let (new_lines, _) = split_lines(text);
+    if range.end == lines.len() {
+        trailing_newline = true;
+    }
+    lines.splice(range, new_lines);
+    join_lines(&lines, trailing_newline)
+}
+
+/// Read-modify-write an existing note under the Notes lock, checking
+/// `if_match` against the contents before `update` sees them.
+fn update_note(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    if_match: Option<&str>,
+    update: impl FnOnce(&str) -> Result<String>,
+) -> Result<PathBuf> {
+    let root = canonical_notes_root(notes_dir)?;
+    let _lock = lock_notes(&root)?;
+    let target = resolve_existing_note_file(&root, path)?;
+    let content = fs::read_to_string(&target)
+        .with_context(|| format!("Failed to read note {}", target.display()))?;
+    if let Some(expected) = if_match {
+        check_revision(&target, expected, Some(content.as_bytes()))?;
+    }
+    let updated = update(&content)?;
+    write_atomic(&target, updated)
+        .with_context(|| format!("Failed to write note {}", target.display()))?;
+    Ok(target)
+}
+
+/// Parse the frontmatter block of a note under the Notes root.
+pub fn read_note_meta(notes_dir: Option<PathBuf>, path: &Path) -> Result<Frontmatter> {
+    let root = canonical_notes_root(notes_dir)?;
+    let target = resolve_existing_note_file(&root, path)?;
+    let content = fs::read_to_string(&target)
+        .with_context(|| format!("Failed to read note {}", target.display()))?;
+    Ok(Frontmatter::parse(&content))
+}
+
+/// Set one frontmatter key of a note, creating the block if the note has none.
+pub fn set_note_meta(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    key: &str,
+    value: &str,
+) -> Result<PathBuf> {
+    frontmatter::validate_key(key)?;
+    if value.contains(['\n', '\r']) {
+        bail!("Frontmatter values must be a single line");
+    }
+    let root = canonical_notes_root(notes_dir)?;
+    let _lock = lock_notes(&root)?;
+    let target = resolve_existing_note_file(&root, path)?;
+    let content = fs::read_to_string(&target)
+        .with_context(|| format!("Failed to read note {}", target.display()))?;
+    let mut frontmatter = frontmatter::with_block(&content);
+    frontmatter.set(key, value);
+    write_atomic(&target, frontmatter.to_string())
         .with_context(|| format!("Failed to write note {}", target.display()))?;
     Ok(target)
 }
 
 pub fn cat_note(notes_dir: Option<PathBuf>, path: &Path) -> Result<String> {
-    read_note(notes_dir, path, None, false)
+    read_note(notes_dir, path, None, None, false)
 }
 
 pub fn head_note(notes_dir: Option<PathBuf>, path: &Path, count: usize) -> Result<String> {
@@ -249,32 +590,73 @@ pub fn tail_note(notes_dir: Option<PathBuf>, path: &Path, count: usize) -> Resul
 }
 
 pub fn wc_note(notes_dir: Option<PathBuf>, path: &Path, flags: WcFlags) -> Result<String> {
+    let counts = wc_counts(notes_dir, path)?;
+    Ok(render_wc(&counts, flags, None))
+}
+
+pub fn wc_counts(notes_dir: Option<PathBuf>, path: &Path) -> Result<WcCounts> {
     let root = canonical_notes_root(notes_dir)?;
     let target = resolve_existing_note_file(&root, path)?;
     let content = fs::read_to_string(&target)
         .with_context(|| format!("Failed to read note {}", target.display()))?;
 
-    let line_count = content.bytes().filter(|byte| *byte == b'\n').count();
-    let word_count = content.split_whitespace().count();
-    let byte_count = content.as_bytes().len();
-    let char_count = content.chars().count();
+    Ok(WcCounts {
+        lines: content.bytes().filter(|byte| *byte == b'\n').count(),
+        words: content.split_whitespace().count(),
+        bytes: content.len(),
+        chars: content.chars().count(),
+        path: target,
+    })
+}
 
-    let mut rows = Vec::new();
+/// Render selected counts; `None` keeps the `name: value` text output.
+pub fn render_wc(counts: &WcCounts, flags: WcFlags, format: Option<OutputFormat>) -> String {
     let any_flags = flags.lines || flags.words || flags.bytes || flags.chars;
-    if !any_flags || flags.lines {
-        rows.push(format!("lines: {line_count}"));
-    }
-    if !any_flags || flags.words {
-        rows.push(format!("words: {word_count}"));
-    }
-    if !any_flags || flags.bytes {
-        rows.push(format!("bytes: {byte_count}"));
-    }
-    if !any_flags || flags.chars {
-        rows.push(format!("chars: {char_count}"));
+    let selected = [
+        ("lines", flags.lines, counts.lines),
+        ("words", flags.words, counts.words),
+        ("bytes", flags.bytes, counts.bytes),
+        ("chars", flags.chars, counts.chars),
+    ]
+    .into_iter()
+    .filter(|(_, wanted, _)| !any_flags || *wanted)
+    .map(|(name, _, value)| (name, value))
+    .collect::<Vec<(&str, usize)>>();
+
+    match format {
+        None => {
+            let rows = selected
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect::<Vec<String>>();
+            rows.join("\n") + "\n"
+        }
+        Some(OutputFormat::Tsv) => render_tsv(
+            &selected
+                .iter()
+                .map(|(name, value)| vec![name.to_string(), value.to_string()])
+                .collect::<Vec<Vec<String>>>(),
+        ),
+        Some(OutputFormat::Table) => {
+            let headers = selected
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<&str>>();
+            let row = selected
+                .iter()
+                .map(|(_, value)| value.to_string())
+                .collect::<Vec<String>>();
+            render_table(&headers, &[row])
+        }
+        Some(OutputFormat::Json) => {
+            let mut object = serde_json::Map::new();
+            object.insert("path".to_string(), json!(counts.path));
+            for (name, value) in &selected {
+                object.insert(name.to_string(), json!(value));
+            }
+            render_json(&Value::Object(object))
+        }
     }
-
-    Ok(rows.join("\n") + "\n")
 }
 
 pub fn less_note(notes_dir: Option<PathBuf>, path: &Path) -> Result<Option<String>> {
@@ -283,12 +665,14 @@ pub fn less_note(notes_dir: Option<PathBuf>, path: &Path) -> Result<Option<Strin
 
     let is_tty = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
     if is_tty && std::env::var_os("PMAN_FORCE_CAT").is_none() {
-        let status = Command::new("less")
-            .arg(&target)
+        let pager = config::setting("pager")?
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| "less".to_string());
+        let status = open::shell_process(&pager, &target)
             .status()
-            .with_context(|| format!("Failed to run less for {}", target.display()))?;
+            .with_context(|| format!("Failed to run {pager} for {}", target.display()))?;
         if !status.success() {
-            bail!("less exited with non-zero status");
+            bail!("{pager} exited with non-zero status");
         }
         Ok(None)
     } else {
@@ -389,11 +773,14 @@ fn join_lines(lines: &[String], trailing_newline: bool) -> String {
 }
 
 fn read_note_lines(notes_dir: Option<PathBuf>, path: &Path) -> Result<(Vec<String>, bool)> {
+    let content = read_note_content(notes_dir, path)?;
+    Ok(split_lines(&content))
+}
+
+fn read_note_content(notes_dir: Option<PathBuf>, path: &Path) -> Result<String> {
     let root = canonical_notes_root(notes_dir)?;
     let target = resolve_existing_note_file(&root, path)?;
-    let content = fs::read_to_string(&target)
-        .with_context(|| format!("Failed to read note {}", target.display()))?;
-    Ok(split_lines(&content))
+    fs::read_to_string(&target).with_context(|| format!("Failed to read note {}", target.display()))
 }
 
 fn canonical_notes_root(notes_dir: Option<PathBuf>) -> Result<PathBuf> {
@@ -474,6 +861,23 @@ fn resolve_writable_note_file(root: &Path, relative: &Path, create_dirs: bool) -
     Ok(target)
 }
 
+/// Lexically resolve `.` and `..` components without touching the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
 fn ensure_contained(root: &Path, target: &Path) -> Result<()> {
     if target.starts_with(root) {
         return Ok(());
@@ -491,52 +895,63 @@ pub fn create_project(
     status: &str,
     area: Option<&str>,
 ) -> Result<PathBuf> {
+    create_project_with_template(paths, name, status, area, None)
+}
+
+/// Create a project whose README is rendered from a named template instead of
+/// the built-in Summary/Notes/Next skeleton.
+pub fn create_project_with_template(
+    paths: &NotesPaths,
+    name: &str,
+    status: &str,
+    area: Option<&str>,
+    template: Option<&ProjectTemplate>,
+) -> Result<PathBuf> {
+    let template_source = template
+        .map(|template| template::load_template(&paths.root, &template.name))
+        .transpose()?;
+    // Held across reading the registry (next id) and saving the new row.
+    let _lock = lock_notes(&paths.root)?;
     ensure_registry(paths)?;
+    let mut registry = Registry::load(&paths.registry)?;
 
-    let (project_id, dir_name, project_name, area_section) = if let Some(explicit_dir_name) =
-        explicit_project_dir_name(name)?
-    {
-        if area.is_some() {
-            bail!("--area is not supported with explicit project names");
-        }
-        if dir_name_in_use(paths, &explicit_dir_name) {
-            bail!("Project already exists in Projects or Archives: {explicit_dir_name}");
-        }
-        (
-            project_id_from_dir(&explicit_dir_name)?,
-            explicit_dir_name.clone(),
-            explicit_dir_name,
-            String::new(),
-        )
-    } else {
-        let registry_contents = fs::read_to_string(&paths.registry)
-            .with_context(|| format!("Failed to read registry at {}", paths.registry.display()))?;
-        let next_id = next_project_id(&registry_contents);
-        let slug = slugify(name)?;
-        let area_slug = area.map(slugify).transpose()?;
-        let project_prefix = project_dir_prefix();
-
-        let slug_full = match area_slug.as_deref() {
-            Some(area_value) => format!("{area_value}-{slug}"),
-            None => slug.clone(),
-        };
+    let (project_id, dir_name, project_name, area_slug) =
+        if let Some(explicit_dir_name) = explicit_project_dir_name(name)? {
+            if area.is_some() {
+                bail!("--area is not supported with explicit project names");
+            }
+            if dir_name_in_use(paths, &explicit_dir_name) {
+                bail!("Project already exists in Projects or Archives: {explicit_dir_name}");
+            }
+            (
+                project_id_from_dir(&explicit_dir_name)?,
+                explicit_dir_name.clone(),
+                explicit_dir_name,
+                None,
+            )
+        } else {
+            let next_id = next_project_id(&registry);
+            let slug = slugify(name)?;
+            let area_slug = area.map(slugify).transpose()?;
+            let project_prefix = project_dir_prefix()?;
+
+            let slug_full = match area_slug.as_deref() {
+                Some(area_value) => format!("{area_value}-{slug}"),
+                None => slug.clone(),
+            };
 
-        if slug_in_use(paths, &slug_full)? {
-            bail!("Slug already exists in Projects or Archives: {slug_full}");
-        }
+            if slug_in_use(paths, &slug_full)? {
+                bail!("Slug already exists in Projects or Archives: {slug_full}");
+            }
 
-        let dir_name = format!("{project_prefix}-{next_id}-{slug_full}");
-        let area_section = area_slug
-            .as_deref()
-            .map(|value| format!("area: {value}\n"))
-            .unwrap_or_default();
-        (
-            format!("PROJ-{next_id}"),
-            dir_name,
-            name.to_string(),
-            area_section,
-        )
-    };
+            let dir_name = format!("{project_prefix}-{next_id}-{slug_full}");
+            (
+                format!("PROJ-{next_id}"),
+                dir_name,
+                name.to_string(),
+                area_slug,
+            )
+        };
 
     let note_dir = paths.projects_dir.join(&dir_name);
     let note_path = note_dir.join("README.md");
@@ -545,124 +960,530 @@ pub fn create_project(
         bail!("Project note already exists: {}", note_path.display());
     }
 
+    let created_dir = !note_dir.exists();
     fs::create_dir_all(&note_dir)
         .with_context(|| format!("Failed to create project directory {}", note_dir.display()))?;
 
     let created = Local::now().format("%Y-%m-%d");
-    let content = format!(
-        "---\nstatus: {status}\n{area}---\n\n# {id}: {name}\n\n**Created**: {created}\n\n## Summary\n- \n\n## Notes\n- \n\n## Next\n- \n",
-        id = project_id,
-        name = project_name,
-        created = created,
-        status = status,
-        area = area_section
-    );
+    let content = match (template, template_source) {
+        (Some(template), Some(source)) => render_project_template(
+            &source,
+            template,
+            &project_id,
+            &project_name,
+            &created.to_string(),
+            status,
+            area_slug.as_deref(),
+        ),
+        _ => format!(
+            "---\nstatus: {status}\n{area}---\n\n# {id}: {name}\n\n**Created**: {created}\n\n## Summary\n- \n\n## Notes\n- \n\n## Next\n- \n",
+            id = project_id,
+            name = project_name,
+            created = created,
+            status = status,
+            area = area_slug
+                .as_deref()
+                .map(|value| format!("area: {value}\n"))
+                .unwrap_or_default()
+        ),
+    };
+
+    registry.push(RegistryEntry::new(
+        &project_id,
+        &project_name,
+        status,
+        &created.to_string(),
+        &format!("{dir_name}/README.md"),
+    ));
+    let result = write_atomic(&note_path, content)
+        .with_context(|| format!("Failed to write note {}", note_path.display()))
+        .and_then(|()| registry.save(&paths.registry));
+    if let Err(err) = result {
+        // Don't leave a project directory behind without a registry row.
+        if created_dir {
+            let _ = fs::remove_dir_all(&note_dir);
+        } else {
+            let _ = fs::remove_file(&note_path);
+        }
+        return Err(err);
+    }
 
-    fs::write(&note_path, content)
-        .with_context(|| format!("Failed to write note {}", note_path.display()))?;
+    Ok(note_path)
+}
 
-    let registry_line = format!(
-        "| {id} | {name} | {status} | {created} | [{dir}/README.md]({dir}/README.md) |",
-        id = project_id,
-        name = project_name,
-        status = status,
-        created = created,
-        dir = dir_name
+/// Fill template placeholders and make sure the README frontmatter carries
+/// the project status (and area), so `pman status` and `list --where` work.
+fn render_project_template(
+    source: &str,
+    template: &ProjectTemplate,
+    id: &str,
+    name: &str,
+    created: &str,
+    status: &str,
+    area: Option<&str>,
+) -> String {
+    let mut values = vec![
+        ("id", id),
+        ("name", name),
+        ("created", created),
+        ("status", status),
+        ("area", area.unwrap_or_default()),
+    ];
+    values.extend(
+        template
+            .vars
+            .iter()
+            .map(|var| (var.key.as_str(), var.value.as_str())),
     );
+    let rendered = template::render_template(source, &values);
 
-    let mut registry = OpenOptions::new()
-        .append(true)
-        .open(&paths.registry)
-        .with_context(|| format!("Failed to open registry {}", paths.registry.display()))?;
-    writeln!(registry, "{registry_line}")?;
-
-    Ok(note_path)
+    let mut frontmatter = frontmatter::with_block(&rendered);
+    if frontmatter.get("status").as_deref() != Some(status) {
+        frontmatter.set_first("status", status);
+    }
+    if let Some(area) = area
+        && frontmatter.get("area").is_none()
+    {
+        frontmatter.set("area", area);
+    }
+    frontmatter.to_string()
 }
 
 pub fn list_projects(paths: &NotesPaths, status: Option<&str>) -> Result<String> {
-    let contents = fs::read_to_string(&paths.registry)
-        .with_context(|| format!("Failed to read registry at {}", paths.registry.display()))?;
-    let wanted_status = status.map(|value| value.to_ascii_lowercase());
-    let mut rows = Vec::new();
+    let records = project_records(paths, status)?;
+    Ok(render_projects(&records, None))
+}
 
-    for line in contents.lines() {
-        if !line.starts_with("| ") || line.starts_with("| ---") {
-            continue;
-        }
+/// Registry rows, optionally filtered by status (case-insensitive).
+pub fn project_records(paths: &NotesPaths, status: Option<&str>) -> Result<Vec<ProjectRecord>> {
+    let registry = Registry::load(&paths.registry)?;
+    Ok(registry
+        .entries()
+        .filter(|entry| status.is_none_or(|wanted| entry.status.eq_ignore_ascii_case(wanted)))
+        .map(|entry| ProjectRecord::from_entry(paths, entry))
+        .collect())
+}
 
-        let parts = line
-            .trim_matches('|')
-            .split('|')
-            .map(|part| part.trim().to_string())
-            .collect::<Vec<String>>();
-        if parts.len() < 5 {
-            continue;
-        }
+/// Keep projects whose note frontmatter matches every filter.
+pub fn filter_projects_by_meta(
+    records: Vec<ProjectRecord>,
+    filters: &[MetaFilter],
+) -> Vec<ProjectRecord> {
+    if filters.is_empty() {
+        return records;
+    }
+    records
+        .into_iter()
+        .filter(|record| {
+            // Missing or unreadable notes have no frontmatter to match.
+            let Ok(content) = fs::read_to_string(&record.path) else {
+                return false;
+            };
+            let frontmatter = Frontmatter::parse(&content);
+            filters.iter().all(|filter| frontmatter.matches(filter))
+        })
+        .collect()
+}
 
-        if let Some(wanted) = &wanted_status {
-            if parts[2].to_ascii_lowercase() != *wanted {
-                continue;
-            }
+/// Registry row for a project directory in `Projects/` or `Archives/Projects/`.
+pub fn project_record(paths: &NotesPaths, dir_name: &str) -> Result<ProjectRecord> {
+    let registry = Registry::load(&paths.registry)?;
+    let entry = registry
+        .find_by_dir_name(dir_name)
+        .with_context(|| format!("Registry entry not found for project directory {dir_name}"))?;
+    Ok(ProjectRecord::from_entry(paths, entry))
+}
+
+/// Render project rows; `None` keeps the default tab-separated listing.
+pub fn render_projects(records: &[ProjectRecord], format: Option<OutputFormat>) -> String {
+    match format {
+        Some(OutputFormat::Json) => render_json(&Value::Array(
+            records.iter().map(ProjectRecord::to_json).collect(),
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(
+            &records
+                .iter()
+                .map(ProjectRecord::tsv_row)
+                .collect::<Vec<Vec<String>>>(),
+        ),
+        _ if records.is_empty() => "No projects found.\n".to_string(),
+        None => render_tsv(
+            &records
+                .iter()
+                .map(ProjectRecord::tsv_row)
+                .collect::<Vec<Vec<String>>>(),
+        ),
+        Some(OutputFormat::Table) => render_table(
+            &["id", "status", "name", "created", "note"],
+            &records
+                .iter()
+                .map(ProjectRecord::table_row)
+                .collect::<Vec<Vec<String>>>(),
+        ),
+    }
+}
+
+pub fn render_project(record: &ProjectRecord, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => render_json(&record.to_json()),
+        _ => render_projects(std::slice::from_ref(record), Some(format)),
+    }
+}
+
+impl ProjectRecord {
+    fn from_entry(paths: &NotesPaths, entry: &RegistryEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            status: entry.status.clone(),
+            created: entry.created.clone(),
+            note: entry.note_path().to_string(),
+            note_cell: entry.note_link.clone(),
+            path: normalize_path(&paths.projects_dir.join(entry.note_path())),
         }
+    }
 
-        rows.push(format!(
-            "{}\t{}\t{}\t{}",
-            parts[0], parts[2], parts[1], parts[4]
-        ));
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "status": self.status,
+            "created": self.created,
+            "note": self.note,
+            "path": self.path,
+        })
     }
 
-    if rows.is_empty() {
-        return Ok("No projects found.\n".to_string());
+    fn tsv_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.status.clone(),
+            self.name.clone(),
+            self.note_cell.clone(),
+        ]
     }
 
-    Ok(rows.join("\n") + "\n")
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.status.clone(),
+            self.name.clone(),
+            self.created.clone(),
+            self.note.clone(),
+        ]
+    }
 }
 
 pub fn archive_project(paths: &NotesPaths, input: &str) -> Result<PathBuf> {
-    let src_dir = find_project_dir(&paths.projects_dir, input)?;
-    let dir_name = src_dir
-        .file_name()
-        .and_then(|name| name.to_str())
-        .context("Project directory name is not valid UTF-8")?;
+    let _lock = lock_notes(&paths.root)?;
+    let project = resolve_project(paths, input)?;
+    if project.archived {
+        bail!(
+            "{} is already archived: {}",
+            project.id,
+            project.dir.display()
+        );
+    }
+    let src_dir = project.dir;
+    let dir_name = project.dir_name.as_str();
 
     let dest_dir = paths.archives_projects_dir.join(dir_name);
     if dest_dir.exists() {
         bail!("Archive target already exists: {}", dest_dir.display());
     }
 
+    // Prepare the registry change before touching the filesystem so a missing
+    // row fails without moving anything.
+    let mut registry = Registry::load(&paths.registry)?;
+    let note_path = if src_dir.join("README.md").exists() {
+        format!("../Archives/Projects/{dir}/README.md", dir = dir_name)
+    } else {
+        format!("../Archives/Projects/{dir}/", dir = dir_name)
+    };
+    let entry = registry
+        .find_by_dir_name_mut(dir_name)
+        .with_context(|| format!("Registry entry not found for project directory {dir_name}"))?;
+    entry.status = "archived".to_string();
+    entry.note_link = RegistryEntry::link_for(&note_path);
+
     fs::create_dir_all(&paths.archives_projects_dir).with_context(|| {
         format!(
             "Failed to create archive directory {}",
             paths.archives_projects_dir.display()
         )
     })?;
-    fs::rename(&src_dir, &dest_dir)
-        .with_context(|| format!("Failed to move project to {}", dest_dir.display()))?;
-
-    let proj_id = project_id_from_registry_note_path(&paths.registry, dir_name)?;
-    let note_path = if dest_dir.join("README.md").exists() {
-        format!("../Archives/Projects/{dir}/README.md", dir = dir_name)
-    } else {
-        format!("../Archives/Projects/{dir}/", dir = dir_name)
-    };
-
-    update_registry_entry(&paths.registry, &proj_id, &note_path)?;
+    move_project_dir(&src_dir, &dest_dir, || registry.save(&paths.registry))?;
 
     Ok(dest_dir)
 }
 
-fn ensure_registry(paths: &NotesPaths) -> Result<()> {
-    if paths.registry.exists() {
-        return Ok(());
+/// Rename a project directory, then run `commit` (the registry save). When the
+/// commit fails the directory is moved back so disk and registry stay in sync.
+fn move_project_dir(
+    src_dir: &Path,
+    dest_dir: &Path,
+    commit: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    fs::rename(src_dir, dest_dir)
+        .with_context(|| format!("Failed to move project to {}", dest_dir.display()))?;
+    if let Err(err) = commit() {
+        if let Err(rollback) = fs::rename(dest_dir, src_dir) {
+            return Err(err.context(format!(
+                "Failed to move project back to {} ({rollback}); it is now at {}",
+                src_dir.display(),
+                dest_dir.display()
+            )));
+        }
+        return Err(err);
     }
+    Ok(())
+}
 
-    fs::create_dir_all(&paths.projects_dir).with_context(|| {
-        format!(
+/// Move an archived project back into `Projects/` and restore its registry row.
+///
+/// `status` must be one of the statuses named in `transitions`; there is no
+/// `archived` edge to check, so any configured status may be restored.
+pub fn unarchive_project(
+    paths: &NotesPaths,
+    input: &str,
+    status: &str,
+    transitions: &StatusTransitions,
+) -> Result<PathBuf> {
+    let status = status.trim().to_ascii_lowercase();
+    if status.is_empty() || status == "archived" {
+        bail!("Unarchived projects need a non-archived status");
+    }
+    let statuses = transitions.statuses();
+    if !statuses.contains(&status.as_str()) {
+        bail!(
+            "Unknown status {status}; configured statuses: {}",
+            statuses.join(", ")
+        );
+    }
+
+    let _lock = lock_notes(&paths.root)?;
+    let mut registry = Registry::load(&paths.registry)?;
+    let project = resolve_project(paths, input)?;
+    if !project.archived {
+        bail!(
+            "Archived project directory not found: {} is active at {}",
+            project.id,
+            project.dir.display()
+        );
+    }
+    let src_dir = project.dir;
+    let dir_name = project.dir_name;
+    if registry.find_by_dir_name(&dir_name).is_none() {
+        bail!("Registry entry not found for project directory {dir_name}");
+    }
+
+    let dest_dir = paths.projects_dir.join(&dir_name);
+    if dest_dir.exists() {
+        bail!("Project directory already exists: {}", dest_dir.display());
+    }
+
+    let original = src_dir.join("README.md");
+    let original = if original.exists() {
+        Some(
+            fs::read_to_string(&original)
+                .with_context(|| format!("Failed to read note {}", original.display()))?,
+        )
+    } else {
+        None
+    };
+    let note_path = if original.is_some() {
+        format!("{dir_name}/README.md")
+    } else {
+        format!("{dir_name}/")
+    };
+    let entry = registry
+        .find_by_dir_name_mut(&dir_name)
+        .with_context(|| format!("Registry entry not found for project directory {dir_name}"))?;
+    entry.status = status.clone();
+    entry.note_link = RegistryEntry::link_for(&note_path);
+
+    // The README and the registry change together; a failed registry save
+    // restores the README before the directory is moved back.
+    let readme = dest_dir.join("README.md");
+    move_project_dir(&src_dir, &dest_dir, || {
+        let Some(original) = original else {
+            return registry.save(&paths.registry);
+        };
+        write_atomic(&readme, set_frontmatter_status(&original, &status))
+            .with_context(|| format!("Failed to write note {}", readme.display()))?;
+        if let Err(err) = registry.save(&paths.registry) {
+            if let Err(restore) = write_atomic(&readme, &original) {
+                return Err(err.context(format!(
+                    "Failed to restore {} ({restore})",
+                    readme.display()
+                )));
+            }
+            return Err(err);
+        }
+        Ok(())
+    })?;
+
+    Ok(dest_dir)
+}
+
+impl StatusTransitions {
+    /// Parse a comma-separated list of `from>to` pairs, e.g. `draft>active,active>done`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut edges = Vec::new();
+        for pair in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let Some((from, to)) = pair.split_once('>') else {
+                bail!("Invalid status transition {pair}; expected from>to");
+            };
+            let from = from.trim().to_ascii_lowercase();
+            let to = to.trim().to_ascii_lowercase();
+            if from.is_empty() || to.is_empty() {
+                bail!("Invalid status transition {pair}; expected from>to");
+            }
+            edges.push((from, to));
+        }
+        if edges.is_empty() {
+            bail!("Status transitions must list at least one from>to pair");
+        }
+        Ok(Self { edges })
+    }
+
+    /// Transitions from `PMAN_STATUS_TRANSITIONS` or `status_transitions` in
+    /// the config, or the default draft -> active -> review -> done lifecycle.
+    pub fn configured() -> Result<Self> {
+        match config::setting_with_source("status_transitions")? {
+            Some((value, source)) => Self::parse(&value)
+                .with_context(|| format!("Invalid status_transitions ({source})")),
+            None => Self::parse(DEFAULT_STATUS_TRANSITIONS),
+        }
+    }
+
+    /// Every status named in the transition list, in first-seen order.
+    pub fn statuses(&self) -> Vec<&str> {
+        let mut statuses: Vec<&str> = Vec::new();
+        for (from, to) in &self.edges {
+            for status in [from.as_str(), to.as_str()] {
+                if !statuses.contains(&status) {
+                    statuses.push(status);
+                }
+            }
+        }
+        statuses
+    }
+
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.edges.iter().any(|(edge_from, edge_to)| {
+            edge_from.eq_ignore_ascii_case(from) && edge_to.eq_ignore_ascii_case(to)
+        })
+    }
+
+    fn targets_from(&self, from: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(edge_from, _)| edge_from.eq_ignore_ascii_case(from))
+            .map(|(_, to)| to.as_str())
+            .collect()
+    }
+}
+
+/// Move a project to a new status, updating the registry row and the
+/// `status:` frontmatter of its README together.
+pub fn set_project_status(
+    paths: &NotesPaths,
+    project: &str,
+    status: &str,
+    transitions: &StatusTransitions,
+    force: bool,
+) -> Result<StatusChange> {
+    let status = status.trim().to_ascii_lowercase();
+    if status.is_empty() {
+        bail!("Status must not be empty");
+    }
+    if status == "archived" {
+        bail!("Use 'pman archive' to archive a project");
+    }
+    let _lock = lock_notes(&paths.root)?;
+
+    let mut registry = Registry::load(&paths.registry)?;
+    let resolved = resolve_project(paths, project)?;
+    // Even with --force: the directory would stay in Archives/Projects/.
+    if resolved.archived {
+        bail!("{} is archived; use 'pman unarchive'", resolved.id);
+    }
+    let id = resolved
+        .entry
+        .with_context(|| {
+            format!(
+                "Registry entry not found for project directory {}",
+                resolved.dir_name
+            )
+        })?
+        .id;
+    let entry = registry
+        .find_mut(&id)
+        .with_context(|| format!("Registry entry not found for {id}"))?;
+    let from = entry.status.clone();
+
+    if from.eq_ignore_ascii_case(&status) {
+        bail!("{id} is already {status}");
+    }
+    if !force && !transitions.allows(&from, &status) {
+        let allowed = transitions.targets_from(&from);
+        if allowed.is_empty() {
+            bail!("No transitions allowed from {from} for {id} (use --force to override)");
+        }
+        bail!(
+            "Transition {from} -> {status} is not allowed for {id}; allowed: {} (use --force to override)",
+            allowed.join(", ")
+        );
+    }
+
+    let note = normalize_path(&paths.projects_dir.join(entry.note_path()));
+    if !note.is_file() {
+        bail!("Project note not found: {}", note.display());
+    }
+    let original = fs::read_to_string(&note)
+        .with_context(|| format!("Failed to read note {}", note.display()))?;
+    entry.status = status.clone();
+
+    write_atomic(&note, set_frontmatter_status(&original, &status))
+        .with_context(|| format!("Failed to write note {}", note.display()))?;
+    if let Err(err) = registry.save(&paths.registry) {
+        // Keep the note and registry in agreement when the registry write fails.
+        let _ = write_atomic(&note, &original);
+        return Err(err);
+    }
+
+    Ok(StatusChange {
+        id,
+        from,
+        to: status,
+        note,
+    })
+}
+
+/// Set the `status:` key of a note's frontmatter, adding a block if missing.
+fn set_frontmatter_status(content: &str, status: &str) -> String {
+    let mut frontmatter = frontmatter::with_block(content);
+    frontmatter.set_first("status", status);
+    frontmatter.to_string()
+}
+
+fn ensure_registry(paths: &NotesPaths) -> Result<()> {
+    if paths.registry.exists() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&paths.projects_dir).with_context(|| {
+        format!(
             "Failed to create projects directory {}",
             paths.projects_dir.display()
         )
     })?;
-    fs::write(&paths.registry, REGISTRY_HEADER).with_context(|| {
+    write_atomic(&paths.registry, REGISTRY_HEADER).with_context(|| {
         format!(
             "Failed to create registry file {}",
             paths.registry.display()
@@ -672,17 +1493,8 @@ fn ensure_registry(paths: &NotesPaths) -> Result<()> {
     Ok(())
 }
 
-fn next_project_id(registry_contents: &str) -> u32 {
-    let re = Regex::new(r"PROJ-(\d+)").expect("valid regex");
-    let mut max_id = 0u32;
-    for cap in re.captures_iter(registry_contents) {
-        if let Ok(num) = cap[1].parse::<u32>() {
-            if num > max_id {
-                max_id = num;
-            }
-        }
-    }
-    max_id + 1
+fn next_project_id(registry: &Registry) -> u32 {
+    registry.next_id("PROJ")
 }
 
 fn explicit_project_dir_name(input: &str) -> Result<Option<String>> {
@@ -779,37 +1591,6 @@ fn slug_matches_dir(name: &str, slug: &str) -> bool {
     tail == slug
 }
 
-fn find_project_dir(projects_dir: &Path, input: &str) -> Result<PathBuf> {
-    let direct = projects_dir.join(input);
-    if direct.exists() {
-        return Ok(direct);
-    }
-
-    let mut matches = Vec::new();
-    for entry in fs::read_dir(projects_dir)
-        .with_context(|| format!("Failed to read {}", projects_dir.display()))?
-    {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
-        }
-        let name = entry.file_name();
-        let name = match name.to_str() {
-            Some(value) => value,
-            None => continue,
-        };
-        if name.starts_with(&format!("{input}-")) {
-            matches.push(entry.path());
-        }
-    }
-
-    match matches.len() {
-        0 => bail!("No project directory matching {input}"),
-        1 => Ok(matches.remove(0)),
-        _ => bail!("Multiple matches for {input}"),
-    }
-}
-
 fn project_id_from_dir(dir_name: &str) -> Result<String> {
     if !dir_name
         .chars()
@@ -838,87 +1619,6 @@ fn project_id_from_dir(dir_name: &str) -> Result<String> {
     Ok(dir_name.to_ascii_uppercase())
 }
 
-fn project_id_from_registry_note_path(registry: &Path, dir_name: &str) -> Result<String> {
-    let contents = fs::read_to_string(registry)
-        .with_context(|| format!("Failed to read registry {}", registry.display()))?;
-
-    for line in contents.lines() {
-        if !line.starts_with("| ") || line.starts_with("| ---") {
-            continue;
-        }
-        let parts = line
-            .trim_matches('|')
-            .split('|')
-            .map(|part| part.trim().to_string())
-            .collect::<Vec<String>>();
-        if parts.len() < 5 {
-            continue;
-        }
-
-        if registry_note_cell_matches_dir_name(&parts[4], dir_name) {
-            return Ok(parts[0].clone());
-        }
-    }
-
-    bail!("Registry entry not found for project directory {dir_name}")
-}
-
-fn registry_note_cell_matches_dir_name(cell: &str, dir_name: &str) -> bool {
-    let Some(link_start) = cell.find("](") else {
-        return false;
-    };
-    let rest = &cell[(link_start + 2)..];
-    let Some(link_end) = rest.find(')') else {
-        return false;
-    };
-    let target = &rest[..link_end];
-    let trimmed = target.trim_start_matches("./");
-    let trimmed = trimmed.strip_suffix("/README.md").unwrap_or(trimmed);
-    let trimmed = trimmed.trim_end_matches('/');
-    let actual = trimmed.rsplit('/').next().unwrap_or_default();
-    actual == dir_name
-}
-
-fn update_registry_entry(registry: &Path, proj_id: &str, note_path: &str) -> Result<()> {
-    let mut lines = fs::read_to_string(registry)
-        .with_context(|| format!("Failed to read registry {}", registry.display()))?
-        .lines()
-        .map(|line| line.to_string())
-        .collect::<Vec<String>>();
-
-    let mut updated = false;
-    for line in &mut lines {
-        if !line.starts_with(&format!("| {proj_id} |")) {
-            continue;
-        }
-
-        let mut parts = line
-            .trim_matches('|')
-            .split('|')
-            .map(|part| part.trim().to_string())
-            .collect::<Vec<String>>();
-        if parts.len() < 5 {
-            bail!("Registry entry malformed for {proj_id}");
-        }
-
-        parts[2] = "archived".to_string();
-        parts[4] = format!("[{note}]({note})", note = note_path);
-        *line = format!("| {} |", parts.join(" | "));
-        updated = true;
-        break;
-    }
-
-    if !updated {
-        bail!("Registry entry not found for {proj_id}");
-    }
-
-    let updated_contents = lines.join("\n") + "\n";
-    fs::write(registry, updated_contents)
-        .with_context(|| format!("Failed to write registry {}", registry.display()))?;
-
-    Ok(())
-}
-
 fn find_notes_root_from_path(path: &Path) -> Option<PathBuf> {
     for ancestor in path.ancestors() {
         if ancestor.join("Projects").is_dir() && ancestor.join("Archives").is_dir() {
@@ -937,22 +1637,47 @@ fn parse_bool_env(value: &str) -> Option<bool> {
     }
 }
 
-fn forced_tool_presence(tool: &str) -> Option<bool> {
+fn forced_tool_presence(tool: &str) -> Result<Option<(bool, String)>> {
     let env_name = match tool {
         "claude" => FORCE_CLAUDE_PRESENT_ENV_VAR,
         "codex" => FORCE_CODEX_PRESENT_ENV_VAR,
-        _ => return None,
+        _ => return Ok(None),
     };
-    std::env::var(env_name)
+    if let Some(forced) = std::env::var(env_name)
         .ok()
         .and_then(|value| parse_bool_env(&value))
+    {
+        return Ok(Some((forced, format!("forced by {env_name}"))));
+    }
+    // A configured `agents` list replaces PATH detection.
+    let Some((agents, source)) = config::setting_with_source("agents")? else {
+        return Ok(None);
+    };
+    Ok(Some((
+        agents.split(',').any(|agent| agent.trim() == tool),
+        format!("agents ({source})"),
+    )))
 }
 
-fn is_tool_available(tool: &str) -> bool {
-    if let Some(forced) = forced_tool_presence(tool) {
-        return forced;
+fn is_tool_available(tool: &str) -> Result<bool> {
+    Ok(tool_presence(tool)?.0)
+}
+
+/// Whether the skill gets bridged for `tool`, and why.
+fn tool_presence(tool: &str) -> Result<(bool, String)> {
+    if let Some(forced) = forced_tool_presence(tool)? {
+        return Ok(forced);
     }
+    let found = tool_on_path(tool);
+    let reason = if found {
+        "found on PATH"
+    } else {
+        "not on PATH"
+    };
+    Ok((found, reason.to_string()))
+}
 
+fn tool_on_path(tool: &str) -> bool {
     let Some(path_var) = std::env::var_os("PATH") else {
         return false;
     };
@@ -1096,7 +1821,7 @@ fn ensure_file(path: &Path, content: &str, overwrite: bool) -> Result<bool> {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create {}", parent.display()))?;
     }
-    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    write_atomic(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
     Ok(true)
 }
 
@@ -1172,7 +1897,7 @@ pub fn init_workspace(workspace: &Path) -> Result<()> {
                 .display()
         );
     } else {
-        fs::write(&registry, REGISTRY_HEADER)
+        write_atomic(&registry, REGISTRY_HEADER)
             .with_context(|| format!("Failed to create {}", registry.display()))?;
         println!(
             "  create: {}",
@@ -1202,7 +1927,7 @@ pub fn init_workspace(workspace: &Path) -> Result<()> {
         );
     }
 
-    if is_tool_available("claude") {
+    if is_tool_available("claude")? {
         let claude_md = workspace.join("CLAUDE.md");
         let changed = ensure_symlink(&agents_md, &claude_md, false, false)?;
         println!(
@@ -1235,7 +1960,7 @@ pub fn init_workspace(workspace: &Path) -> Result<()> {
     }
 
     let canonical_skill_dir = canonical_skill_dir(workspace);
-    if is_tool_available("claude") {
+    if is_tool_available("claude")? {
         let link = claude_skill_link(workspace);
         let changed = ensure_symlink(&canonical_skill_dir, &link, false, true)?;
         println!(
@@ -1245,7 +1970,7 @@ pub fn init_workspace(workspace: &Path) -> Result<()> {
         );
     }
 
-    if is_tool_available("codex") {
+    if is_tool_available("codex")? {
         let link = codex_skill_link(workspace);
         let changed = ensure_symlink(&canonical_skill_dir, &link, false, true)?;
         println!(
@@ -1262,9 +1987,26 @@ pub fn init_workspace(workspace: &Path) -> Result<()> {
 /// Verify workspace setup and report any issues.
 /// Returns true if all checks pass, false otherwise.
 pub fn verify_workspace(workspace: &Path) -> Result<bool> {
-    println!("Verifying pman workspace at {}", workspace.display());
+    let report = verify_report(workspace)?;
+    print!("{}", render_verify(&report, None));
+    Ok(report.ok())
+}
+
+/// Collect workspace checks without printing them.
+pub fn verify_report(workspace: &Path) -> Result<VerifyReport> {
+    let mut checks = Vec::new();
+    let mut check = |name: &str, ok: bool, problem: &str| {
+        checks.push(VerifyCheck {
+            name: name.to_string(),
+            ok,
+            problem: if ok {
+                String::new()
+            } else {
+                problem.to_string()
+            },
+        });
+    };
 
-    let mut all_ok = true;
     let notes_dirs = [
         ("Notes/Projects", workspace.join("Notes").join("Projects")),
         ("Notes/Areas", workspace.join("Notes").join("Areas")),
@@ -1276,84 +2018,111 @@ pub fn verify_workspace(workspace: &Path) -> Result<bool> {
     ];
 
     for (name, path) in &notes_dirs {
-        if path.exists() {
-            println!("  ✓ {}", name);
-        } else {
-            println!("  ✗ {} (missing)", name);
-            all_ok = false;
-        }
+        check(name, path.exists(), "missing");
     }
 
     let registry = workspace
         .join("Notes")
         .join("Projects")
         .join("_registry.md");
-    if registry.exists() {
-        println!("  ✓ Notes/Projects/_registry.md");
-    } else {
-        println!("  ✗ Notes/Projects/_registry.md (missing)");
-        all_ok = false;
-    }
+    check("Notes/Projects/_registry.md", registry.exists(), "missing");
 
     let agents_md = workspace.join("AGENTS.md");
-    if agents_md.exists() {
-        println!("  ✓ AGENTS.md");
-    } else {
-        println!("  ✗ AGENTS.md (missing)");
-        all_ok = false;
-    }
+    check("AGENTS.md", agents_md.exists(), "missing");
 
-    let canonical_skill_file = canonical_skill_file(workspace);
-    if canonical_skill_file.exists() {
-        println!("  ✓ .pman/skills/project/SKILL.md");
-    } else {
-        println!("  ✗ .pman/skills/project/SKILL.md (missing)");
-        all_ok = false;
-    }
+    check(
+        ".pman/skills/project/SKILL.md",
+        canonical_skill_file(workspace).exists(),
+        "missing",
+    );
 
-    if is_tool_available("claude") {
+    if is_tool_available("claude")? {
         let claude_md = workspace.join("CLAUDE.md");
-        if symlink_matches(&claude_md, &agents_md)? {
-            println!("  ✓ CLAUDE.md -> AGENTS.md");
-        } else {
-            println!("  ✗ CLAUDE.md -> AGENTS.md (missing or incorrect symlink)");
-            all_ok = false;
-        }
+        check(
+            "CLAUDE.md -> AGENTS.md",
+            symlink_matches(&claude_md, &agents_md)?,
+            "missing or incorrect symlink",
+        );
 
         let link = claude_skill_link(workspace);
         let target = canonical_skill_dir(workspace);
-        if symlink_matches(&link, &target)? {
-            println!("  ✓ .claude/skills/project -> .pman/skills/project");
-        } else {
-            println!(
-                "  ✗ .claude/skills/project -> .pman/skills/project (missing or incorrect symlink)"
-            );
-            all_ok = false;
-        }
+        check(
+            ".claude/skills/project -> .pman/skills/project",
+            symlink_matches(&link, &target)?,
+            "missing or incorrect symlink",
+        );
     }
 
-    if is_tool_available("codex") {
+    if is_tool_available("codex")? {
         let link = codex_skill_link(workspace);
         let target = canonical_skill_dir(workspace);
-        if symlink_matches(&link, &target)? {
-            println!("  ✓ .codex/skills/project -> .pman/skills/project");
-        } else {
-            println!(
-                "  ✗ .codex/skills/project -> .pman/skills/project (missing or incorrect symlink)"
-            );
-            all_ok = false;
-        }
-    }
-
-    if all_ok {
-        println!("\nWorkspace OK.");
-    } else {
-        println!(
-            "\nIssues found. Run 'pman init' to create missing directories, or 'pman update' to restore AGENTS.md and skills."
+        check(
+            ".codex/skills/project -> .pman/skills/project",
+            symlink_matches(&link, &target)?,
+            "missing or incorrect symlink",
         );
     }
 
-    Ok(all_ok)
+    Ok(VerifyReport {
+        workspace: workspace.to_path_buf(),
+        checks,
+    })
+}
+
+/// Render a verify report; `None` keeps the ✓/✗ checklist output.
+pub fn render_verify(report: &VerifyReport, format: Option<OutputFormat>) -> String {
+    let status = |check: &VerifyCheck| if check.ok { "ok" } else { "fail" }.to_string();
+    match format {
+        None => {
+            let mut output = format!(
+                "Verifying pman workspace at {}\n",
+                report.workspace.display()
+            );
+            for check in &report.checks {
+                if check.ok {
+                    output.push_str(&format!("  ✓ {}\n", check.name));
+                } else {
+                    output.push_str(&format!("  ✗ {} ({})\n", check.name, check.problem));
+                }
+            }
+            if report.ok() {
+                output.push_str("\nWorkspace OK.\n");
+            } else {
+                output.push_str(
+                    "\nIssues found. Run 'pman init' to create missing directories, or 'pman update' to restore AGENTS.md and skills.\n",
+                );
+            }
+            output
+        }
+        Some(OutputFormat::Json) => render_json(&json!({
+            "workspace": report.workspace,
+            "ok": report.ok(),
+            "checks": report
+                .checks
+                .iter()
+                .map(|check| json!({
+                    "name": check.name,
+                    "ok": check.ok,
+                    "problem": if check.ok { Value::Null } else { json!(check.problem) },
+                }))
+                .collect::<Vec<Value>>(),
+        })),
+        Some(OutputFormat::Tsv) => render_tsv(
+            &report
+                .checks
+                .iter()
+                .map(|check| vec![status(check), check.name.clone(), check.problem.clone()])
+                .collect::<Vec<Vec<String>>>(),
+        ),
+        Some(OutputFormat::Table) => render_table(
+            &["status", "check", "problem"],
+            &report
+                .checks
+                .iter()
+                .map(|check| vec![status(check), check.name.clone(), check.problem.clone()])
+                .collect::<Vec<Vec<String>>>(),
+        ),
+    }
 }
 
 /// Update AGENTS.md and skills to the latest embedded versions.
@@ -1370,7 +2139,7 @@ pub fn update_workspace(workspace: &Path) -> Result<()> {
     println!("  update: .pman/skills/project/SKILL.md");
 
     let canonical_skill_dir = canonical_skill_dir(workspace);
-    if is_tool_available("claude") {
+    if is_tool_available("claude")? {
         let claude_md = workspace.join("CLAUDE.md");
         ensure_symlink(&agents_md, &claude_md, true, false)?;
         println!("  update: CLAUDE.md -> AGENTS.md");
@@ -1380,7 +2149,7 @@ pub fn update_workspace(workspace: &Path) -> Result<()> {
         println!("  update: .claude/skills/project -> .pman/skills/project");
     }
 
-    if is_tool_available("codex") {
+    if is_tool_available("codex")? {
         let link = codex_skill_link(workspace);
         ensure_symlink(&canonical_skill_dir, &link, true, true)?;
         println!("  update: .codex/skills/project -> .pman/skills/project");
@@ -1507,8 +2276,10 @@ mod tests {
 
     #[test]
     fn next_project_id_increments() {
-        let registry = "| PROJ-0002 | Example |\n| PROJ-2 | Example |";
-        assert_eq!(next_project_id(registry), 3);
+        let registry = Registry::parse(&format!(
+            "{REGISTRY_HEADER}| PROJ-0002 | Example | active | 2026-01-01 | [a](a) |\n| PROJ-2 | Example | active | 2026-01-01 | [b](b) |\n"
+        ));
+        assert_eq!(next_project_id(&registry), 3);
     }
 
     #[test]
@@ -1554,6 +2325,124 @@ mod tests {
         assert!(updated.contains("../Archives/Projects/proj-3-sample/README.md"));
     }
 
+    #[test]
+    fn archive_project_rolls_back_move_when_registry_write_fails() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        fs::create_dir_all(&paths.archives_projects_dir).unwrap();
+        let proj_dir = paths.projects_dir.join("proj-3-sample");
+        fs::create_dir_all(&proj_dir).unwrap();
+        fs::write(proj_dir.join("README.md"), "test").unwrap();
+        let registry = format!(
+            "{header}| PROJ-3 | Sample | active | 2025-01-01 | [proj-3-sample/README.md](proj-3-sample/README.md) |\n",
+            header = REGISTRY_HEADER
+        );
+        fs::write(&paths.registry, &registry).unwrap();
+
+        // Block the registry's temp file so the atomic save fails after the move.
+        let blocker = paths
+            .projects_dir
+            .join(format!("._registry.md.pman-{}.tmp", std::process::id()));
+        fs::create_dir(&blocker).unwrap();
+        assert!(archive_project(&paths, "proj-3").is_err());
+        assert!(proj_dir.join("README.md").exists());
+        assert!(!paths.archives_projects_dir.join("proj-3-sample").exists());
+        assert_eq!(fs::read_to_string(&paths.registry).unwrap(), registry);
+
+        // Without a registry row nothing is moved at all.
+        fs::remove_dir(&blocker).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+        let err = archive_project(&paths, "proj-3").unwrap_err().to_string();
+        assert!(err.contains("Registry entry not found"));
+        assert!(proj_dir.exists());
+    }
+
+    #[test]
+    fn archive_project_resolves_registry_id_and_slug() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(paths.projects_dir.join("proj-12-payment-retry")).unwrap();
+        fs::write(
+            &paths.registry,
+            format!(
+                "{REGISTRY_HEADER}| PROJ-12 | Payment Retry | active | 2026-01-01 | [proj-12-payment-retry/README.md](proj-12-payment-retry/README.md) |\n"
+            ),
+        )
+        .unwrap();
+
+        let archived = archive_project(&paths, "PROJ-12").unwrap();
+        assert_eq!(
+            archived,
+            paths.archives_projects_dir.join("proj-12-payment-retry")
+        );
+        let err = archive_project(&paths, "payment-retry")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("PROJ-12 is already archived"));
+        let transitions = StatusTransitions::parse(DEFAULT_STATUS_TRANSITIONS).unwrap();
+        unarchive_project(&paths, "payment retry", "active", &transitions).unwrap();
+    }
+
+    #[test]
+    fn unarchive_project_restores_directory_and_registry() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::create_dir_all(&paths.archives_projects_dir).unwrap();
+
+        let proj_dir = paths.projects_dir.join("proj-3-sample");
+        fs::create_dir_all(&proj_dir).unwrap();
+        fs::write(proj_dir.join("README.md"), "---\nstatus: active\n---\n").unwrap();
+
+        let registry = format!(
+            "{header}| PROJ-3 | Sample | active | 2025-01-01 | [proj-3-sample/README.md](proj-3-sample/README.md) |\n",
+            header = REGISTRY_HEADER
+        );
+        fs::write(&paths.registry, registry).unwrap();
+
+        archive_project(&paths, "proj-3").unwrap();
+        let transitions = StatusTransitions::parse(DEFAULT_STATUS_TRANSITIONS).unwrap();
+        let err = unarchive_project(&paths, "PROJ-3", "paused", &transitions)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown status paused; configured statuses: draft, active"));
+
+        // A failed registry save leaves the README and the directory untouched.
+        let blocker = paths
+            .projects_dir
+            .join(format!("._registry.md.pman-{}.tmp", std::process::id()));
+        fs::create_dir(&blocker).unwrap();
+        assert!(unarchive_project(&paths, "PROJ-3", "review", &transitions).is_err());
+        let archived = paths.archives_projects_dir.join("proj-3-sample");
+        assert_eq!(
+            fs::read_to_string(archived.join("README.md")).unwrap(),
+            "---\nstatus: active\n---\n"
+        );
+        assert!(!proj_dir.exists());
+        fs::remove_dir(&blocker).unwrap();
+
+        let restored = unarchive_project(&paths, "PROJ-3", "review", &transitions).unwrap();
+
+        assert_eq!(restored, proj_dir);
+        assert!(!paths.archives_projects_dir.join("proj-3-sample").exists());
+        let updated = fs::read_to_string(&paths.registry).unwrap();
+        assert!(updated.contains(
+            "| PROJ-3 | Sample | review | 2025-01-01 | [proj-3-sample/README.md](proj-3-sample/README.md) |"
+        ));
+        assert!(
+            fs::read_to_string(proj_dir.join("README.md"))
+                .unwrap()
+                .contains("status: review")
+        );
+
+        let err = unarchive_project(&paths, "proj-3", "active", &transitions)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Archived project directory not found"));
+    }
+
     #[test]
     fn create_project_includes_area_slug() {
         let _lock = notes_env_lock();
@@ -1573,6 +2462,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_project_renders_named_template() {
+        let _lock = notes_env_lock();
+        let _prefix_guard = ProjectPrefixEnvGuard::set("proj");
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::create_dir_all(root.join("Resources").join("Templates")).unwrap();
+        fs::write(
+            root.join("Resources").join("Templates").join("bugfix.md"),
+            "---\nticket: {ticket}\n---\n\n# {id}: {name} ({area})\n\n## Repro\n- \n",
+        )
+        .unwrap();
+
+        let template = ProjectTemplate {
+            name: "bugfix".to_string(),
+            vars: vec!["ticket=PAY-12".parse().unwrap()],
+        };
+        let note_path = create_project_with_template(
+            &paths,
+            "Retry Storm",
+            "active",
+            Some("payments"),
+            Some(&template),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&note_path).unwrap();
+        assert_eq!(
+            content,
+            "---\nstatus: active\nticket: PAY-12\narea: payments\n---\n\n# PROJ-1: Retry Storm (payments)\n\n## Repro\n- \n"
+        );
+        let registry = fs::read_to_string(&paths.registry).unwrap();
+        assert!(registry.contains("| PROJ-1 | Retry Storm | active |"));
+
+        let missing = ProjectTemplate {
+            name: "rfc".to_string(),
+            vars: Vec::new(),
+        };
+        let err = create_project_with_template(&paths, "Other", "active", None, Some(&missing))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("available templates: bugfix"));
+        assert!(!paths.projects_dir.join("proj-2-other").exists());
+    }
+
+    #[test]
+    fn concurrent_create_project_allocates_unique_ids() {
+        let _lock = notes_env_lock();
+        let _prefix_guard = ProjectPrefixEnvGuard::set("proj");
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+
+        let handles = (0..8)
+            .map(|index| {
+                let paths = paths.clone();
+                std::thread::spawn(move || {
+                    create_project(&paths, &format!("Parallel {index}"), "active", None).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let registry = Registry::load(&paths.registry).unwrap();
+        let mut ids = registry
+            .entries()
+            .map(|entry| entry.id.clone())
+            .collect::<Vec<String>>();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 8);
+    }
+
     #[test]
     fn create_project_uses_configured_prefix_from_env() {
         let _lock = notes_env_lock();
@@ -1727,13 +2694,14 @@ mod tests {
         fs::create_dir_all(&paths.projects_dir).unwrap();
 
         let registry = format!(
-            "{header}| PROJ-1 | One | active | 2026-02-14 | [proj-1-one/README.md](proj-1-one/README.md) |\n| PROJ-2 | Two | archived | 2026-02-14 | [../Archives/Projects/proj-2-two/README.md](../Archives/Projects/proj-2-two/README.md) |\n",
+            "{header}| PROJ-1 | One | active | 2026-02-14 | [proj-1-one/README.md](proj-1-one/README.md) |\n| PROJ-2 | Two | archived | 2026-02-14 | [../Archives/Projects/proj-2-two/README.md](../Archives/Projects/proj-2-two/README.md) |\n| PROJ-3 | Three | active | 2026-02-14 | proj-3-three/README.md |\n",
             header = REGISTRY_HEADER
         );
         fs::write(&paths.registry, registry).unwrap();
 
         let active = list_projects(&paths, Some("active")).unwrap();
         assert!(active.contains("PROJ-1\tactive\tOne"));
+        assert!(active.contains("PROJ-3\tactive\tThree\tproj-3-three/README.md\n"));
         assert!(!active.contains("PROJ-2"));
 
         let all = list_projects(&paths, None).unwrap();
@@ -1741,6 +2709,194 @@ mod tests {
         assert!(all.contains("PROJ-2\tarchived\tTwo"));
     }
 
+    #[test]
+    fn set_project_status_updates_registry_and_frontmatter() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+        let note = create_project(&paths, "status-7-demo", "draft", None).unwrap();
+        let transitions = StatusTransitions::parse(DEFAULT_STATUS_TRANSITIONS).unwrap();
+
+        let change = set_project_status(&paths, "STATUS-7", "active", &transitions, false).unwrap();
+        assert_eq!(change.from, "draft");
+        assert_eq!(change.to, "active");
+
+        let registry = fs::read_to_string(&paths.registry).unwrap();
+        assert!(registry.contains("| STATUS-7 | status-7-demo | active |"));
+        let content = fs::read_to_string(&note).unwrap();
+        assert!(content.starts_with("---\nstatus: active\n---\n"));
+    }
+
+    #[test]
+    fn set_project_status_refuses_archived_projects() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+        create_project(&paths, "status-8-demo", "active", None).unwrap();
+        archive_project(&paths, "STATUS-8").unwrap();
+        let transitions = StatusTransitions::parse(DEFAULT_STATUS_TRANSITIONS).unwrap();
+
+        let err = set_project_status(&paths, "STATUS-8", "active", &transitions, true)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "STATUS-8 is archived; use 'pman unarchive'");
+        let registry = fs::read_to_string(&paths.registry).unwrap();
+        assert!(registry.contains("| STATUS-8 | status-8-demo | archived |"));
+    }
+
+    #[test]
+    fn set_project_status_enforces_transitions() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+        let note = create_project(&paths, "status-8-demo", "draft", None).unwrap();
+        let transitions = StatusTransitions::parse("draft>active, active>done").unwrap();
+        assert_eq!(transitions.statuses(), vec!["draft", "active", "done"]);
+
+        let err = set_project_status(&paths, "status-8", "done", &transitions, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("draft -> done is not allowed"));
+        assert!(err.contains("allowed: active"));
+        assert!(fs::read_to_string(&note).unwrap().contains("status: draft"));
+
+        set_project_status(&paths, "status-8", "done", &transitions, true).unwrap();
+        assert!(fs::read_to_string(&note).unwrap().contains("status: done"));
+
+        let err = set_project_status(&paths, "STATUS-8", "archived", &transitions, true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("pman archive"));
+    }
+
+    #[test]
+    fn set_note_meta_feeds_list_where_filters() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        for dir in ["proj-1-one", "proj-2-two"] {
+            fs::create_dir_all(paths.projects_dir.join(dir)).unwrap();
+            fs::write(
+                paths.projects_dir.join(dir).join("README.md"),
+                "---\nstatus: active\n---\n\n# T\n",
+            )
+            .unwrap();
+        }
+        let registry = format!(
+            "{header}| PROJ-1 | One | active | 2026-02-14 | [proj-1-one/README.md](proj-1-one/README.md) |\n| PROJ-2 | Two | active | 2026-02-14 | [proj-2-two/README.md](proj-2-two/README.md) |\n",
+            header = REGISTRY_HEADER
+        );
+        fs::write(&paths.registry, registry).unwrap();
+
+        let note = Path::new("Projects/proj-2-two/README.md");
+        set_note_meta(Some(root.to_path_buf()), note, "owner", "ada").unwrap();
+        set_note_meta(Some(root.to_path_buf()), note, "area", "payments").unwrap();
+        assert!(set_note_meta(Some(root.to_path_buf()), note, "bad key", "x").is_err());
+        let frontmatter = read_note_meta(Some(root.to_path_buf()), note).unwrap();
+        assert_eq!(frontmatter.get("owner").as_deref(), Some("ada"));
+
+        let filters = vec![
+            "area=Payments".parse::<MetaFilter>().unwrap(),
+            "owner=ada".parse::<MetaFilter>().unwrap(),
+        ];
+        let records = filter_projects_by_meta(project_records(&paths, None).unwrap(), &filters);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "PROJ-2");
+    }
+
+    #[test]
+    fn set_frontmatter_status_inserts_missing_key_or_block() {
+        assert_eq!(
+            set_frontmatter_status("---\narea: ops\n---\n# T\n", "review"),
+            "---\nstatus: review\narea: ops\n---\n# T\n"
+        );
+        assert_eq!(
+            set_frontmatter_status("# T\n", "review"),
+            "---\nstatus: review\n---\n\n# T\n"
+        );
+    }
+
+    #[test]
+    fn create_project_escapes_pipes_in_registry_name() {
+        let _lock = notes_env_lock();
+        let _prefix_guard = ProjectPrefixEnvGuard::set("proj");
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+        fs::write(&paths.registry, REGISTRY_HEADER).unwrap();
+
+        create_project(&paths, "Retry | Backoff", "active", None).unwrap();
+
+        let registry = fs::read_to_string(&paths.registry).unwrap();
+        assert!(registry.contains("| PROJ-1 | Retry \\| Backoff | active |"));
+
+        let all = list_projects(&paths, None).unwrap();
+        assert_eq!(all.lines().count(), 1);
+        assert!(all.starts_with("PROJ-1\tactive\tRetry | Backoff\t"));
+    }
+
+    #[test]
+    fn project_records_render_as_json_and_table() {
+        let temp = tempdir().unwrap();
+        let root = temp.path();
+        let paths = NotesPaths::from_root(root.to_path_buf());
+        fs::create_dir_all(&paths.projects_dir).unwrap();
+
+        let registry = format!(
+            "{header}| PROJ-1 | One | active | 2026-02-14 | [proj-1-one/README.md](proj-1-one/README.md) |\n| PROJ-2 | Two | archived | 2026-02-15 | [../Archives/Projects/proj-2-two/README.md](../Archives/Projects/proj-2-two/README.md) |\n",
+            header = REGISTRY_HEADER
+        );
+        fs::write(&paths.registry, registry).unwrap();
+
+        let records = project_records(&paths, None).unwrap();
+        let parsed: Value =
+            serde_json::from_str(&render_projects(&records, Some(OutputFormat::Json))).unwrap();
+        assert_eq!(parsed[0]["id"], "PROJ-1");
+        assert_eq!(parsed[1]["status"], "archived");
+        assert_eq!(parsed[1]["created"], "2026-02-15");
+        assert_eq!(
+            PathBuf::from(parsed[1]["path"].as_str().unwrap()),
+            paths
+                .archives_projects_dir
+                .join("proj-2-two")
+                .join("README.md")
+        );
+
+        let table = render_projects(&records, Some(OutputFormat::Table));
+        assert!(table.starts_with("ID      STATUS    NAME  CREATED     NOTE\n"));
+
+        let archived = project_record(&paths, "proj-2-two").unwrap();
+        assert_eq!(archived.id, "PROJ-2");
+        assert_eq!(
+            render_projects(&[], Some(OutputFormat::Json)),
+            "[]\n".to_string()
+        );
+    }
+
+    #[test]
+    fn verify_report_renders_json() {
+        let _lock = notes_env_lock();
+        let _claude = EnvVarGuard::set(FORCE_CLAUDE_PRESENT_ENV_VAR, "0");
+        let _codex = EnvVarGuard::set(FORCE_CODEX_PRESENT_ENV_VAR, "0");
+        let temp = tempdir().unwrap();
+        let workspace = temp.path();
+        fs::create_dir_all(workspace.join("Notes").join("Projects")).unwrap();
+
+        let report = verify_report(workspace).unwrap();
+        assert!(!report.ok());
+
+        let parsed: Value =
+            serde_json::from_str(&render_verify(&report, Some(OutputFormat::Json))).unwrap();
+        assert_eq!(parsed["ok"], false);
+        assert_eq!(parsed["checks"][0]["name"], "Notes/Projects");
+        assert_eq!(parsed["checks"][0]["ok"], true);
+        assert_eq!(parsed["checks"][1]["problem"], "missing");
+    }
+
     #[test]
     fn init_workspace_writes_agents_and_canonical_skill_without_agent_bridges() {
         let _lock = notes_env_lock();
@@ -1857,12 +3013,35 @@ mod tests {
             Some(root),
             Path::new("Projects/sample.md"),
             Some("2:3"),
+            None,
             true,
         )
         .unwrap();
         assert_eq!(output, "     2\tbeta\n     3\tgamma\n");
     }
 
+    #[test]
+    fn read_note_selects_sections() {
+        let (_temp, root) = setup_notes_root();
+        fs::write(root.join("plan.md"), "# P\n\n## Plan\n- a\n\n## Next\n- b").unwrap();
+        let rel = Path::new("plan.md");
+
+        let plan = read_note(Some(root.clone()), rel, None, Some("## Plan"), false).unwrap();
+        assert_eq!(plan, "## Plan\n- a\n");
+        let next = read_note(Some(root.clone()), rel, None, Some("next"), true).unwrap();
+        assert_eq!(next, "     6\t## Next\n     7\t- b");
+        assert!(read_note(Some(root.clone()), rel, Some("1:2"), Some("Plan"), false).is_err());
+
+        let outline = note_outline(Some(root), rel).unwrap();
+        assert_eq!(
+            outline
+                .iter()
+                .map(|heading| heading.line)
+                .collect::<Vec<usize>>(),
+            vec![1, 3, 6]
+        );
+    }
+
     #[test]
     fn edit_note_enforces_expected_text_guard() {
         let (_temp, root) = setup_notes_root();
@@ -1876,6 +3055,7 @@ mod tests {
             "2:2",
             "updated",
             Some("wrong\n"),
+            None,
         )
         .unwrap_err()
         .to_string();
@@ -1887,12 +3067,178 @@ mod tests {
             "2:2",
             "updated",
             Some("two\n"),
+            None,
         )
         .unwrap();
         let updated = fs::read_to_string(&file).unwrap();
         assert_eq!(updated, "one\nupdated\nthree\n");
     }
 
+    #[test]
+    fn write_and_edit_reject_stale_revisions() {
+        let (_temp, root) = setup_notes_root();
+        let rel = Path::new("Projects/rev.md");
+        fs::create_dir_all(root.join("Projects")).unwrap();
+        fs::write(root.join(rel), "one\ntwo\n").unwrap();
+
+        let (content, revision) =
+            read_note_with_revision(Some(root.clone()), rel, Some("2:2"), None, false).unwrap();
+        assert_eq!(content, "two\n");
+        assert_eq!(revision, note_revision("one\ntwo\n"));
+
+        // Someone edits the note after it was read.
+        fs::write(root.join(rel), "one\ntwo\nthree\n").unwrap();
+        let err = write_note(Some(root.clone()), rel, "mine", false, Some(&revision)).unwrap_err();
+        let mismatch = err.downcast_ref::<RevisionMismatch>().unwrap();
+        assert_eq!(mismatch.actual, Some(note_revision("one\ntwo\nthree\n")));
+        let err =
+            edit_note(Some(root.clone()), rel, "1:1", "x", None, Some(&revision)).unwrap_err();
+        assert!(err.downcast_ref::<RevisionMismatch>().is_some());
+        assert_eq!(
+            fs::read_to_string(root.join(rel)).unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        let current = note_revision("one\ntwo\nthree\n");
+        edit_note(Some(root.clone()), rel, "1:1", "1\n", None, Some(&current)).unwrap();
+        let err = write_note(
+            Some(root.clone()),
+            Path::new("Projects/new.md"),
+            "x",
+            false,
+            Some(&current),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("found no file"));
+    }
+
+    #[test]
+    fn replace_in_note_honours_first_all_and_count_guard() {
+        let (_temp, root) = setup_notes_root();
+        let rel = Path::new("todo.md");
+        fs::write(root.join(rel), "- [ ] a (x)\n- [ ] b (x)\n").unwrap();
+
+        let (_, replaced) = replace_in_note(
+            Some(root.clone()),
+            rel,
+            "(x)",
+            "$1",
+            ReplaceOptions::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(replaced, 1);
+        assert_eq!(
+            fs::read_to_string(root.join(rel)).unwrap(),
+            "- [ ] a $1\n- [ ] b (x)\n"
+        );
+
+        let guarded = ReplaceOptions {
+            regex: true,
+            all: true,
+            expect_count: Some(3),
+        };
+        let err = replace_in_note(
+            Some(root.clone()),
+            rel,
+            r"- \[ \] (\w)",
+            "- [x] $1",
+            guarded,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Expected 3 match(es)"));
+
+        let all = ReplaceOptions {
+            expect_count: Some(2),
+            ..guarded
+        };
+        let (_, replaced) = replace_in_note(
+            Some(root.clone()),
+            rel,
+            r"- \[ \] (\w)",
+            "- [x] $1",
+            all,
+            None,
+        )
+        .unwrap();
+        assert_eq!(replaced, 2);
+        assert_eq!(
+            fs::read_to_string(root.join(rel)).unwrap(),
+            "- [x] a $1\n- [x] b (x)\n"
+        );
+
+        let err = replace_in_note(
+            Some(root.clone()),
+            rel,
+            "missing",
+            "y",
+            ReplaceOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No match"));
+
+        let anchored = ReplaceOptions {
+            regex: true,
+            all: true,
+            expect_count: Some(1),
+        };
+        let (_, replaced) = replace_in_note(
+            Some(root.clone()),
+            rel,
+            r"^- \[x\] b(.*)$",
+            "- b$1",
+            anchored,
+            None,
+        )
+        .unwrap();
+        assert_eq!(replaced, 1);
+        assert_eq!(
+            fs::read_to_string(root.join(rel)).unwrap(),
+            "- [x] a $1\n- b (x)\n"
+        );
+
+        assert!(
+            replace_in_note(
+                Some(root),
+                Path::new("../escape.md"),
+                "a",
+                "b",
+                ReplaceOptions::default(),
+                None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn append_and_insert_place_lines() {
+        let (_temp, root) = setup_notes_root();
+        let rel = Path::new("README.md");
+        fs::write(
+            root.join(rel),
+            "# PROJ-1: X\n\n## Notes\n- \n\n## Next\n- ship\n\n",
+        )
+        .unwrap();
+
+        append_note(Some(root.clone()), rel, "- first\n", Some("## Notes"), None).unwrap();
+        append_note(Some(root.clone()), rel, "- second", Some("notes"), None).unwrap();
+        append_note(Some(root.clone()), rel, "- review", Some("Next"), None).unwrap();
+        insert_note(Some(root.clone()), rel, 0, "---\nstatus: active\n---", None).unwrap();
+        append_note(Some(root.clone()), rel, "footer", None, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join(rel)).unwrap(),
+            "---\nstatus: active\n---\n# PROJ-1: X\n\n## Notes\n- first\n- second\n\n## Next\n- ship\n- review\n\nfooter\n"
+        );
+
+        let err = append_note(Some(root.clone()), rel, "x", Some("## Done"), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("available sections: # PROJ-1: X, ## Notes, ## Next"));
+        assert!(insert_note(Some(root), rel, 99, "x", None).is_err());
+    }
+
     #[test]
     fn edit_note_replaces_empty_file_range() {
         let (_temp, root) = setup_notes_root();
@@ -1906,6 +3252,7 @@ mod tests {
             "1:1",
             "hello\nworld\n",
             None,
+            None,
         )
         .unwrap();
 
@@ -1918,7 +3265,7 @@ mod tests {
         let (_temp, root) = setup_notes_root();
         let target_rel = Path::new("Areas/team/notes.md");
 
-        write_note(Some(root.clone()), target_rel, "body", true).unwrap();
+        write_note(Some(root.clone()), target_rel, "body", true, None).unwrap();
         let updated = fs::read_to_string(root.join(target_rel)).unwrap();
         assert_eq!(updated, "body");
     }
@@ -1934,7 +3281,7 @@ mod tests {
         let link = root.join("escape");
         symlink(&outside, &link).unwrap();
 
-        let err = write_note(Some(root), Path::new("escape/evil.md"), "bad", false)
+        let err = write_note(Some(root), Path::new("escape/evil.md"), "bad", false, None)
             .unwrap_err()
             .to_string();
         assert!(err.contains("escapes notes root"));
@@ -1959,6 +3306,33 @@ mod tests {
         assert_eq!(output, "words: 3\n");
     }
 
+    #[test]
+    fn render_wc_supports_structured_formats() {
+        let counts = WcCounts {
+            path: PathBuf::from("/notes/wc.md"),
+            lines: 2,
+            words: 3,
+            bytes: 9,
+            chars: 9,
+        };
+        let flags = WcFlags {
+            lines: true,
+            words: true,
+            ..WcFlags::default()
+        };
+
+        assert_eq!(render_wc(&counts, flags, None), "lines: 2\nwords: 3\n");
+        assert_eq!(
+            render_wc(&counts, flags, Some(OutputFormat::Tsv)),
+            "lines\t2\nwords\t3\n"
+        );
+        let parsed: Value =
+            serde_json::from_str(&render_wc(&counts, flags, Some(OutputFormat::Json))).unwrap();
+        assert_eq!(parsed["lines"], 2);
+        assert_eq!(parsed["path"], "/notes/wc.md");
+        assert!(parsed.get("bytes").is_none());
+    }
+
     #[test]
     fn less_note_falls_back_to_cat_without_tty() {
         let (_temp, root) = setup_notes_root();
@@ -0,0 +1,434 @@
+use anyhow::{Result, bail};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::output::{render_json, render_table, render_tsv};
+use crate::{ConfigEntry, ConfigSource, OutputFormat, config, config_get, vault};
+
+/// Set by `--verbose`: print how the Notes root was resolved to stderr.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// The trace is printed once per process, even if the root is resolved again.
+static TRACED: AtomicBool = AtomicBool::new(false);
+
+/// One Notes root strategy considered by [`resolve_notes_root`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootCandidate {
+    pub strategy: String,
+    pub path: Option<PathBuf>,
+    /// Why the strategy was passed over; `None` for the chosen one.
+    pub rejected: Option<String>,
+}
+
+/// The Notes root strategies tried in order, ending at the chosen one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotesRootResolution {
+    /// `None` when every strategy was rejected.
+    pub root: Option<PathBuf>,
+    pub candidates: Vec<RootCandidate>,
+}
+
+impl NotesRootResolution {
+    fn reject(&mut self, strategy: impl Into<String>, path: Option<PathBuf>, reason: &str) {
+        self.candidates.push(RootCandidate {
+            strategy: strategy.into(),
+            path,
+            rejected: Some(reason.to_string()),
+        });
+    }
+
+    fn choose(mut self, strategy: impl Into<String>, path: PathBuf) -> Self {
+        self.candidates.push(RootCandidate {
+            strategy: strategy.into(),
+            path: Some(path.clone()),
+            rejected: None,
+        });
+        self.root = Some(path);
+        self
+    }
+
+    /// The chosen strategy, if any.
+    pub fn chosen(&self) -> Option<&RootCandidate> {
+        self.candidates
+            .iter()
+            .find(|candidate| candidate.rejected.is_none())
+    }
+}
+
+/// Whether `pman init`/`update` bridge the project skill into one agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentBridge {
+    pub agent: String,
+    pub enabled: bool,
+    pub reason: String,
+    pub link: Option<PathBuf>,
+    pub link_present: bool,
+}
+
+/// Everything `pman where` reports.
+#[derive(Debug, Clone)]
+pub struct WhereReport {
+    pub resolution: NotesRootResolution,
+    pub workspace: Option<PathBuf>,
+    pub workspace_config: Option<PathBuf>,
+    pub user_config: Option<PathBuf>,
+    pub project_prefix: ConfigEntry,
+    pub agents: Vec<AgentBridge>,
+}
+
+/// Print the resolution trace on stderr from now on (`--verbose`).
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Try each Notes root strategy in precedence order, recording why the
+/// earlier ones were passed over.
+pub fn resolve_notes_root(notes_dir: Option<PathBuf>) -> Result<NotesRootResolution> {
+    let mut trace = NotesRootResolution::default();
+    let selected = vault::selected_vault();
+
+    match (notes_dir, selected) {
+        (Some(path), Some(name)) => bail!(
+            "Use either --vault {name} or --notes-dir {}, not both",
+            path.display()
+        ),
+        (Some(path), None) => return Ok(trace.choose("--notes-dir", path)),
+        (None, _) => trace.reject("--notes-dir", None, "not given"),
+    }
+
+    match selected {
+        Some(name) => return Ok(trace.choose(format!("--vault {name}"), vault::vault_dir(name)?)),
+        None => trace.reject("--vault", None, "not given"),
+    }
+
+    if let Some((strategy, path)) = try_settings(
+        &mut trace,
+        config::setting_with_source("notes_dir")?,
+        config::setting_with_source("vault")?,
+        vault::vault_dir,
+    )? {
+        return Ok(trace.choose(strategy, path));
+    }
+
+    match std::env::var_os("HOME") {
+        Some(home) => {
+            let default_notes = PathBuf::from(home).join("Notes");
+            if default_notes.exists() {
+                return Ok(trace.choose("~/Notes", default_notes));
+            }
+            trace.reject("~/Notes", Some(default_notes), "does not exist");
+        }
+        None => trace.reject("~/Notes", None, "HOME is not set"),
+    }
+
+    for (strategy, start) in [
+        ("ancestors of the pman executable", std::env::current_exe()),
+        (
+            "ancestors of the current directory",
+            std::env::current_dir(),
+        ),
+    ] {
+        match start {
+            Ok(start) => match crate::find_notes_root_from_path(&start) {
+                Some(path) => return Ok(trace.choose(strategy, path)),
+                None => trace.reject(
+                    strategy,
+                    Some(start),
+                    "no ancestor has both Projects/ and Archives/",
+                ),
+            },
+            Err(err) => trace.reject(strategy, None, &err.to_string()),
+        }
+    }
+
+    Ok(trace)
+}
+
+/// Try the `notes_dir` and `vault` settings: both environment variables first,
+/// then both config file values, so `PMAN_VAULT` beats a `notes_dir` from a
+/// config file. Returns the chosen strategy and root.
+fn try_settings(
+    trace: &mut NotesRootResolution,
+    notes_dir: Option<(String, ConfigSource)>,
+    vault: Option<(String, ConfigSource)>,
+    vault_dir: impl Fn(&str) -> Result<PathBuf>,
+) -> Result<Option<(String, PathBuf)>> {
+    for from_env in [true, false] {
+        let wanted = |setting: &Option<(String, ConfigSource)>| {
+            setting
+                .clone()
+                .filter(|(_, source)| matches!(source, ConfigSource::Env(_)) == from_env)
+        };
+        let (notes_dir_label, vault_label) = if from_env {
+            (crate::NOTES_DIR_ENV_VAR, vault::VAULT_ENV_VAR)
+        } else {
+            ("notes_dir in config", "vault in config")
+        };
+
+        match wanted(&notes_dir) {
+            Some((path, source)) => {
+                return Ok(Some((format!("notes_dir ({source})"), PathBuf::from(path))));
+            }
+            None => trace.reject(notes_dir_label, None, "not set"),
+        }
+        match wanted(&vault) {
+            Some((name, source)) => {
+                return Ok(Some((
+                    format!("vault {name} ({source})"),
+                    vault_dir(&name)?,
+                )));
+            }
+            None => trace.reject(vault_label, None, "not set"),
+        }
+    }
+    Ok(None)
+}
+
+/// Print `resolution` on stderr once, when `--verbose` is on.
+pub(crate) fn trace_if_verbose(resolution: &NotesRootResolution) {
+    if VERBOSE.load(Ordering::Relaxed) && !TRACED.swap(true, Ordering::Relaxed) {
+        eprint!("{}", render_resolution(resolution));
+    }
+}
+
+pub fn where_report(notes_dir: Option<PathBuf>) -> Result<WhereReport> {
+    let resolution = resolve_notes_root(notes_dir)?;
+    let workspace = config::workspace_dir().or_else(|| {
+        resolution
+            .root
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|parent| parent.join(".pman").is_dir())
+            .map(Path::to_path_buf)
+    });
+    let agents = [
+        ("claude", workspace.as_deref().map(crate::claude_skill_link)),
+        ("codex", workspace.as_deref().map(crate::codex_skill_link)),
+    ]
+    .into_iter()
+    .map(|(agent, link)| {
+        let (enabled, reason) = crate::tool_presence(agent)?;
+        Ok(AgentBridge {
+            agent: agent.to_string(),
+            enabled,
+            reason,
+            link_present: link
+                .as_deref()
+                .is_some_and(|link| link.symlink_metadata().is_ok()),
+            link,
+        })
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    Ok(WhereReport {
+        workspace_config: workspace
+            .as_deref()
+            .map(|dir| dir.join(".pman").join("config.toml"))
+            .filter(|path| path.is_file()),
+        user_config: config::user_config().filter(|path| path.is_file()),
+        workspace,
+        project_prefix: config_get("project_prefix")?,
+        resolution,
+        agents,
+    })
+}
+
+fn render_resolution(resolution: &NotesRootResolution) -> String {
+    let mut output = match &resolution.root {
+        Some(root) => format!("Notes root: {}\n", root.display()),
+        None => "Notes root: not found\n".to_string(),
+    };
+    for candidate in &resolution.candidates {
+        let path = candidate
+            .path
+            .as_ref()
+            .map(|path| format!(": {}", path.display()))
+            .unwrap_or_default();
+        match &candidate.rejected {
+            Some(reason) => {
+                output.push_str(&format!("  ✗ {}{path} ({reason})\n", candidate.strategy))
+            }
+            None => output.push_str(&format!("  ✓ {}{path}\n", candidate.strategy)),
+        }
+    }
+    output
+}
+
+pub fn render_where(report: &WhereReport, format: Option<OutputFormat>) -> String {
+    let display = |path: &Option<PathBuf>| {
+        path.as_ref()
+            .map_or("-".to_string(), |path| path.display().to_string())
+    };
+    let prefix = format!(
+        "{} ({})",
+        report.project_prefix.value.as_deref().unwrap_or("-"),
+        report.project_prefix.source
+    );
+
+    match format {
+        None => {
+            let mut output = render_resolution(&report.resolution);
+            output.push_str(&format!(
+                "Workspace: {}\n",
+                report
+                    .workspace
+                    .as_ref()
+                    .map_or("none found".to_string(), |path| path.display().to_string())
+            ));
+            output.push_str(&format!(
+                "Workspace config: {}\n",
+                display(&report.workspace_config)
+            ));
+            output.push_str(&format!("User config: {}\n", display(&report.user_config)));
+            output.push_str(&format!("Project prefix: {prefix}\n"));
+            output.push_str("Agent bridges:\n");
+            for bridge in &report.agents {
+                let link = match (&bridge.link, bridge.link_present) {
+                    (Some(link), true) => format!("; {} present", link.display()),
+                    (Some(link), false) => format!("; {} missing", link.display()),
+                    (None, _) => String::new(),
+                };
+                output.push_str(&format!(
+                    "  {}: {} ({}{link})\n",
+                    bridge.agent,
+                    if bridge.enabled { "on" } else { "off" },
+                    bridge.reason
+                ));
+            }
+            output
+        }
+        Some(OutputFormat::Json) => render_json(&json!({
+            "notes_root": report.resolution.root,
+            "strategy": report.resolution.chosen().map(|candidate| &candidate.strategy),
+            "candidates": report
+                .resolution
+                .candidates
+                .iter()
+                .map(|candidate| json!({
+                    "strategy": candidate.strategy,
+                    "path": candidate.path,
+                    "chosen": candidate.rejected.is_none(),
+                    "rejected": candidate.rejected,
+                }))
+                .collect::<Vec<_>>(),
+            "workspace": report.workspace,
+            "workspace_config": report.workspace_config,
+            "user_config": report.user_config,
+            "project_prefix": {
+                "value": report.project_prefix.value,
+                "source": report.project_prefix.source.to_string(),
+            },
+            "agents": report
+                .agents
+                .iter()
+                .map(|bridge| json!({
+                    "agent": bridge.agent,
+                    "enabled": bridge.enabled,
+                    "reason": bridge.reason,
+                    "link": bridge.link,
+                    "link_present": bridge.link_present,
+                }))
+                .collect::<Vec<_>>(),
+        })),
+        Some(format) => {
+            let mut rows = vec![
+                vec!["notes_root".to_string(), display(&report.resolution.root)],
+                vec![
+                    "strategy".to_string(),
+                    report
+                        .resolution
+                        .chosen()
+                        .map_or("-".to_string(), |candidate| candidate.strategy.clone()),
+                ],
+                vec!["workspace".to_string(), display(&report.workspace)],
+                vec![
+                    "workspace_config".to_string(),
+                    display(&report.workspace_config),
+                ],
+                vec!["user_config".to_string(), display(&report.user_config)],
+                vec!["project_prefix".to_string(), prefix],
+            ];
+            rows.extend(report.agents.iter().map(|bridge| {
+                vec![
+                    format!("agent.{}", bridge.agent),
+                    format!(
+                        "{} ({})",
+                        if bridge.enabled { "on" } else { "off" },
+                        bridge.reason
+                    ),
+                ]
+            }));
+            match format {
+                OutputFormat::Tsv => render_tsv(&rows),
+                _ => render_table(&["field", "value"], &rows),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_notes_dir_wins_and_trace_lists_rejections() {
+        let resolution = resolve_notes_root(Some(PathBuf::from("/srv/Notes"))).unwrap();
+        assert_eq!(resolution.root, Some(PathBuf::from("/srv/Notes")));
+        assert_eq!(resolution.chosen().unwrap().strategy, "--notes-dir");
+
+        let mut trace = NotesRootResolution::default();
+        trace.reject("--notes-dir", None, "not given");
+        trace.reject(
+            "~/Notes",
+            Some(PathBuf::from("/home/a/Notes")),
+            "does not exist",
+        );
+        let trace = trace.choose(
+            "ancestors of the current directory",
+            PathBuf::from("/w/Notes"),
+        );
+        assert_eq!(
+            render_resolution(&trace),
+            "Notes root: /w/Notes\n  ✗ --notes-dir (not given)\n  ✗ ~/Notes: /home/a/Notes (does not exist)\n  ✓ ancestors of the current directory: /w/Notes\n"
+        );
+    }
+
+    #[test]
+    fn env_vault_beats_notes_dir_from_a_config_file() {
+        let vault_dir = |name: &str| Ok(PathBuf::from(format!("/vaults/{name}")));
+        let config_notes_dir = Some((
+            "/tmp/v1/Notes".to_string(),
+            ConfigSource::User(PathBuf::from("/home/a/.config/pman/config.toml")),
+        ));
+
+        let mut trace = NotesRootResolution::default();
+        let chosen = try_settings(
+            &mut trace,
+            config_notes_dir.clone(),
+            Some(("work".to_string(), ConfigSource::Env("PMAN_VAULT"))),
+            vault_dir,
+        )
+        .unwrap();
+        assert_eq!(
+            chosen,
+            Some((
+                "vault work (env PMAN_VAULT)".to_string(),
+                PathBuf::from("/vaults/work")
+            ))
+        );
+        assert_eq!(trace.candidates.len(), 1);
+        assert_eq!(trace.candidates[0].strategy, "PMAN_NOTES_DIR");
+
+        let mut trace = NotesRootResolution::default();
+        let chosen = try_settings(&mut trace, config_notes_dir, None, vault_dir).unwrap();
+        assert_eq!(chosen.unwrap().1, PathBuf::from("/tmp/v1/Notes"));
+        assert_eq!(
+            trace
+                .candidates
+                .iter()
+                .map(|candidate| candidate.strategy.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["PMAN_NOTES_DIR", "PMAN_VAULT"]
+        );
+    }
+}
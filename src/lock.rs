@@ -0,0 +1,149 @@
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const LOCK_FILE_NAME: &str = ".pman.lock";
+pub(crate) const LOCK_TIMEOUT_ENV_VAR: &str = "PMAN_LOCK_TIMEOUT";
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Notes roots whose lock this thread already holds, so nested
+    /// operations (e.g. doctor fixing a note) don't deadlock on themselves.
+    static HELD: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Exclusive advisory lock on a Notes root, released on drop.
+///
+/// Every registry mutation and note write takes this lock so concurrent pman
+/// processes (CLI, MCP servers, agents) can't interleave read-modify-write
+/// cycles such as allocating the next project id.
+pub(crate) struct NotesLock {
+    /// `None` when this thread already held the lock (re-entrant acquire).
+    file: Option<File>,
+    root: PathBuf,
+}
+
+impl Drop for NotesLock {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            let _ = file.unlock();
+            HELD.with(|held| held.borrow_mut().retain(|root| root != &self.root));
+        }
+    }
+}
+
+/// Acquire the Notes root lock, waiting up to `PMAN_LOCK_TIMEOUT` seconds
+/// (default 10).
+pub(crate) fn lock_notes(root: &Path) -> Result<NotesLock> {
+    lock_notes_with_timeout(root, lock_timeout()?)
+}
+
+pub(crate) fn lock_notes_with_timeout(root: &Path, timeout: Duration) -> Result<NotesLock> {
+    fs::create_dir_all(root)
+        .with_context(|| format!("Failed to create Notes root {}", root.display()))?;
+    let root = fs::canonicalize(root)
+        .with_context(|| format!("Failed to resolve Notes root {}", root.display()))?;
+    if HELD.with(|held| held.borrow().contains(&root)) {
+        return Ok(NotesLock { file: None, root });
+    }
+
+    let path = root.join(LOCK_FILE_NAME);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                std::thread::sleep(RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = holder.trim();
+                bail!(
+                    "Notes root is locked by another pman process{}; gave up after {:.1}s. If no pman process is running, the lock is released automatically; lock file: {}",
+                    if holder.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({holder})")
+                    },
+                    timeout.as_secs_f64(),
+                    path.display()
+                );
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(err).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+    }
+
+    // Record the holder for the error message other processes see.
+    let _ = file.set_len(0);
+    let _ = file.seek(SeekFrom::Start(0));
+    let _ = write!(
+        file,
+        "pid {} since {}",
+        std::process::id(),
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+
+    HELD.with(|held| held.borrow_mut().push(root.clone()));
+    Ok(NotesLock {
+        file: Some(file),
+        root,
+    })
+}
+
+fn lock_timeout() -> Result<Duration> {
+    Ok(crate::config::setting("lock_timeout")?
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(DEFAULT_LOCK_TIMEOUT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn lock_is_reentrant_per_thread_and_exclusive_across_threads() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().to_path_buf();
+
+        let outer = lock_notes_with_timeout(&root, Duration::ZERO).unwrap();
+        let inner = lock_notes_with_timeout(&root, Duration::ZERO).unwrap();
+        drop(inner);
+
+        let other = root.clone();
+        let err = std::thread::spawn(move || {
+            lock_notes_with_timeout(&other, Duration::from_millis(100))
+                .err()
+                .map(|err| err.to_string())
+        })
+        .join()
+        .unwrap()
+        .expect("second thread should time out");
+        assert!(err.contains("locked by another pman process"));
+        assert!(err.contains(&format!("pid {}", std::process::id())));
+
+        drop(outer);
+        let other = root.clone();
+        std::thread::spawn(move || lock_notes_with_timeout(&other, Duration::ZERO).unwrap())
+            .join()
+            .unwrap();
+    }
+}
@@ -1,12 +1,22 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand};
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use pman::{
-    NotesPaths, WcFlags, archive_project, cat_note, create_project, edit_note, generate_skill,
-    head_note, init_workspace, less_note, list_projects, read_note, resolve_notes_dir, tail_note,
-    update_workspace, verify_workspace, wc_note, write_note,
+    DEFAULT_ADR_STATUS, DEFAULT_PATCH_FUZZ, McpServer, MetaFilter, NotesPaths, OutputFormat,
+    ProjectTemplate, ReplaceOptions, RevisionMismatch, SearchOptions, StatusTransitions,
+    TemplateVar, WcFlags, active_vault, add_project_note, append_note, archive_project,
+    attach_file, cat_note, config_get, config_list, config_set, create_project_with_template,
+    default_project_status, doctor, edit_note, filter_projects_by_meta, generate_skill, head_note,
+    init_workspace, insert_note, less_note, list_adrs, new_adr, note_outline, open_note,
+    patch_note, project_note_path, project_record, project_records, read_note, read_note_meta,
+    read_note_with_revision, render_adrs, render_config, render_doctor, render_meta,
+    render_note_json, render_outline, render_patch, render_project, render_projects, render_search,
+    render_show, render_vaults, render_verify, render_wc, render_where, replace_in_note,
+    resolve_notes_dir, resolve_open_target, search_notes, select_vault, set_note_meta,
+    set_project_status, set_verbose, show_project, tail_note, unarchive_project, update_workspace,
+    vaults, verify_report, wc_counts, where_report, write_note,
 };
 
 #[derive(Parser)]
@@ -14,6 +24,15 @@ use pman::{
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Output format for list, new, archive, unarchive, status, show, adr list, read, patch, search, meta get, config get/list, vaults, where, doctor, wc and verify (json, tsv, table)
+    #[arg(long, global = true)]
+    format: Option<OutputFormat>,
+    /// Named vault from [vaults] in ~/.config/pman/config.toml to use as the Notes root
+    #[arg(long, global = true)]
+    vault: Option<String>,
+    /// Print how the Notes root was resolved to stderr
+    #[arg(long, short = 'v', global = true)]
+    verbose: bool,
 }
 
 #[derive(Subcommand)]
@@ -36,24 +55,63 @@ enum Commands {
         #[arg(long, default_value = ".")]
         path: PathBuf,
     },
+    /// Cross-check the registry against project directories and notes
+    Doctor {
+        /// Apply safe repairs
+        #[arg(long)]
+        fix: bool,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
     /// Create a new project note in Notes/Projects
     New {
-        /// Project name, or explicit project directory name (<prefix>-<number>-<slug>; default prefix: proj or PMAN_PROJECT_PREFIX)
+        /// Project name, or explicit project directory name (<prefix>-<number>-<slug>; default prefix: proj, or project_prefix from the config)
         name: String,
-        /// Status label to record in the registry
-        #[arg(long, default_value = "draft")]
-        status: String,
+        /// Status label to record in the registry (default: draft, or default_status from the config)
+        #[arg(long)]
+        status: Option<String>,
         /// Area slug to prefix the project directory slug
         #[arg(long)]
         area: Option<String>,
+        /// README template from Notes/Resources/Templates/ or .pman/templates/
+        #[arg(long)]
+        template: Option<String>,
+        /// Extra template placeholder value (key=value, repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE", requires = "template")]
+        vars: Vec<TemplateVar>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
     },
     /// Archive a project directory into Notes/Archives/Projects
     Archive {
-        /// Project directory name or prefix (e.g. proj-0022 or ticket-0022)
+        /// Project id, directory prefix, slug or name (e.g. PROJ-22, proj-22 or payment-retry)
+        project: String,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Restore an archived project into Notes/Projects
+    Unarchive {
+        /// Archived project id, directory prefix, slug or name (e.g. PROJ-12 or proj-12)
+        project: String,
+        /// Status to set on the restored project (one of the statuses in status_transitions)
+        #[arg(long, default_value = "active")]
+        status: String,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Move a project to a new status (registry and README frontmatter)
+    Status {
+        /// Project id, directory prefix, slug or name (e.g. PROJ-12 or proj-12)
         project: String,
+        /// New status (allowed transitions come from status_transitions in the config)
+        status: String,
+        /// Allow transitions outside the configured set
+        #[arg(long)]
+        force: bool,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
@@ -63,14 +121,55 @@ enum Commands {
         /// Filter by status (default: active, use 'all' for everything)
         #[arg(long, default_value = "active")]
         status: String,
+        /// Filter by project note frontmatter (key=value, repeatable)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<MetaFilter>,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Summarize one project: registry row, frontmatter, files and plan sections
+    Show {
+        /// Project id, directory prefix, slug or name (e.g. PROJ-12 or payment-retry)
+        project: String,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
     },
+    /// Add markdown notes inside a project directory
+    Note {
+        #[command(subcommand)]
+        command: NoteCommands,
+    },
+    /// Copy a file into a project directory
+    Attach {
+        /// Project id, directory prefix, slug or name (e.g. PROJ-12 or payment-retry)
+        project: String,
+        /// File to copy
+        file: PathBuf,
+        /// Destination relative to the project directory (default: the file's name)
+        #[arg(long = "as", value_name = "PATH")]
+        dest: Option<PathBuf>,
+        /// Replace an existing file
+        #[arg(long)]
+        force: bool,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Create and list architecture decision records inside project directories
+    Adr {
+        #[command(subcommand)]
+        command: AdrCommands,
+    },
     /// Read a note file relative to notes root
     Read {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
@@ -80,11 +179,24 @@ enum Commands {
         /// Include line numbers in output
         #[arg(long)]
         numbered: bool,
+        /// Read one heading section, e.g. "## Next"
+        #[arg(long, value_name = "HEADING", conflicts_with = "lines")]
+        section: Option<String>,
+        /// Print the note's heading tree with line numbers
+        #[arg(long, conflicts_with_all = ["lines", "section", "numbered", "revision"])]
+        outline: bool,
+        /// Print the note's revision token to stderr (for --if-match)
+        #[arg(long)]
+        revision: bool,
     },
     /// Replace a note file's full contents
     Write {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
@@ -94,36 +206,153 @@ enum Commands {
         /// Content to write; if omitted, stdin is used
         #[arg(long)]
         content: Option<String>,
+        /// Only write if the note still has this revision (from read --revision)
+        #[arg(long, value_name = "REVISION")]
+        if_match: Option<String>,
     },
-    /// Replace an inclusive line range in a note file
+    /// Replace an inclusive line range, or matching text, in a note file
     Edit {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
         /// Inclusive line range to replace (start:end), 1-based
-        #[arg(long)]
-        replace_lines: String,
+        #[arg(
+            long,
+            required_unless_present = "find",
+            conflicts_with = "find",
+            requires = "with_text"
+        )]
+        replace_lines: Option<String>,
         /// Replacement text for the selected range
-        #[arg(long = "with")]
-        with_text: String,
+        #[arg(long = "with", requires = "replace_lines")]
+        with_text: Option<String>,
         /// Optional expected text guard for stale-context detection
-        #[arg(long)]
+        #[arg(long, requires = "replace_lines")]
         expect: Option<String>,
+        /// Text to find instead of a line range (literal unless --regex)
+        #[arg(long, allow_hyphen_values = true, requires = "replace")]
+        find: Option<String>,
+        /// Replacement for --find matches ($1, ${name} expand with --regex)
+        #[arg(long, allow_hyphen_values = true, requires = "find")]
+        replace: Option<String>,
+        /// Treat --find as a regex
+        #[arg(long, requires = "find")]
+        regex: bool,
+        /// Replace every match instead of only the first
+        #[arg(long, requires = "find")]
+        all: bool,
+        /// Fail unless --find matches exactly this many times
+        #[arg(long, value_name = "N", requires = "find")]
+        expect_count: Option<usize>,
+        /// Only edit if the note still has this revision (from read --revision)
+        #[arg(long, value_name = "REVISION")]
+        if_match: Option<String>,
     },
-    /// Notes-scoped cat wrapper
-    Cat {
+    /// Apply a unified diff to a note file
+    Patch {
         /// Note path relative to notes root
         path: PathBuf,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
+        /// Unified diff to apply; if omitted, stdin is used
+        #[arg(long, allow_hyphen_values = true)]
+        diff: Option<String>,
+        /// Context lines a hunk may ignore at each end to match
+        #[arg(long, default_value_t = DEFAULT_PATCH_FUZZ)]
+        fuzz: usize,
+        /// Only patch if the note still has this revision (from read --revision)
+        #[arg(long, value_name = "REVISION")]
+        if_match: Option<String>,
     },
-    /// Notes-scoped head wrapper
-    Head {
+    /// Append text to the end of a note or of a heading section
+    Append {
         /// Note path relative to notes root
         path: PathBuf,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+        /// Text to append; if omitted, stdin is used
+        #[arg(long, allow_hyphen_values = true)]
+        content: Option<String>,
+        /// Append to the end of this section instead (e.g. "## Notes")
+        #[arg(long, value_name = "HEADING")]
+        section: Option<String>,
+        /// Only append if the note still has this revision (from read --revision)
+        #[arg(long, value_name = "REVISION")]
+        if_match: Option<String>,
+    },
+    /// Insert text after a line of a note
+    Insert {
+        /// Note path relative to notes root
+        path: PathBuf,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+        /// 1-based line to insert after (0 inserts at the top)
+        #[arg(long, value_name = "N")]
+        after_line: usize,
+        /// Text to insert; if omitted, stdin is used
+        #[arg(long, allow_hyphen_values = true)]
+        content: Option<String>,
+        /// Only insert if the note still has this revision (from read --revision)
+        #[arg(long, value_name = "REVISION")]
+        if_match: Option<String>,
+    },
+    /// Read or update note frontmatter
+    Meta {
+        #[command(subcommand)]
+        command: MetaCommands,
+    },
+    /// Search note contents under the Notes root
+    Search {
+        /// Regex pattern (or literal text with --literal)
+        pattern: String,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+        /// Treat the pattern as literal text
+        #[arg(long, short = 'F')]
+        literal: bool,
+        /// Match case-insensitively
+        #[arg(long, short = 'i')]
+        ignore_case: bool,
+        /// Limit to a PARA folder (Projects, Areas, Resources, Archives)
+        #[arg(long = "in", value_name = "FOLDER")]
+        folder: Option<String>,
+        /// Limit to one project (id, directory prefix, slug or name)
+        #[arg(long, conflicts_with = "folder")]
+        project: Option<String>,
+        /// Lines of context before and after each match
+        #[arg(long, short = 'C', default_value_t = 0)]
+        context: usize,
+    },
+    /// Notes-scoped cat wrapper
+    Cat {
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Notes-scoped head wrapper
+    Head {
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Number of lines to show
         #[arg(long, default_value_t = 10)]
         lines: usize,
@@ -133,8 +362,12 @@ enum Commands {
     },
     /// Notes-scoped tail wrapper
     Tail {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Number of lines to show
         #[arg(long, default_value_t = 10)]
         lines: usize,
@@ -144,8 +377,12 @@ enum Commands {
     },
     /// Notes-scoped wc wrapper
     Wc {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
@@ -164,17 +401,50 @@ enum Commands {
     },
     /// Notes-scoped less wrapper
     Less {
-        /// Note path relative to notes root
-        path: PathBuf,
+        /// Note path relative to notes root (or to the project directory with --project)
+        #[arg(required_unless_present = "project")]
+        path: Option<PathBuf>,
+        /// Resolve the path inside this project's directory, active or archived (default: its README.md)
+        #[arg(long)]
+        project: Option<String>,
         /// Override Notes root directory
         #[arg(long)]
         notes_dir: Option<PathBuf>,
     },
+    /// Open a note or project README in $VISUAL or $EDITOR
+    Open {
+        /// Note path relative to notes root, or a project id, slug or name
+        target: String,
+        /// Print the resolved absolute path instead of launching an editor
+        #[arg(long)]
+        print_path: bool,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// List named vaults from the user config with their resolved paths
+    Vaults,
+    /// Explain which Notes root, workspace, config and agent bridges pman uses
+    Where {
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Read and write .pman/config.toml settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
     /// Skill operations
     Skill {
         #[command(subcommand)]
         command: SkillCommands,
     },
+    /// MCP server operations
+    Mcp {
+        #[command(subcommand)]
+        command: McpCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -187,10 +457,132 @@ enum SkillCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum MetaCommands {
+    /// Print one frontmatter value, or every key when no key is given
+    Get {
+        /// Note path relative to notes root
+        path: PathBuf,
+        /// Frontmatter key
+        key: Option<String>,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// Set a frontmatter value, adding the block if the note has none
+    Set {
+        /// Note path relative to notes root
+        path: PathBuf,
+        /// Frontmatter key
+        key: String,
+        /// New value
+        value: String,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum NoteCommands {
+    /// Create <name>.md in a project directory, from a template when one matches
+    Add {
+        /// Project id, directory prefix, slug or name (e.g. PROJ-12 or payment-retry)
+        project: String,
+        /// Note name relative to the project directory, e.g. design or notes/call
+        name: String,
+        /// Template from Notes/Resources/Templates/ or .pman/templates/ (default: one named like the note, if any)
+        #[arg(long)]
+        template: Option<String>,
+        /// Extra template placeholder value (key=value, repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<TemplateVar>,
+        /// Append today's date to the file name (e.g. meeting-2026-03-14.md)
+        #[arg(long)]
+        dated: bool,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdrCommands {
+    /// Create the project's next adr-NNNN-<slug>.md
+    New {
+        /// Project id, directory prefix, slug or name (e.g. PROJ-12 or payment-retry)
+        project: String,
+        /// Decision title
+        title: String,
+        /// Initial status line
+        #[arg(long, default_value = DEFAULT_ADR_STATUS)]
+        status: String,
+        /// Number of an ADR in the same project that this one supersedes
+        #[arg(long, value_name = "NUMBER")]
+        supersedes: Option<u32>,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+    /// List ADRs across all projects and archives, or of one project
+    List {
+        /// Project id, directory prefix, slug or name (default: every project)
+        project: Option<String>,
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective value of one setting and where it came from
+    Get {
+        /// Setting key, e.g. project_prefix
+        key: String,
+    },
+    /// Write a setting to the workspace config (empty value removes it)
+    Set {
+        /// Setting key, e.g. project_prefix
+        key: String,
+        /// New value; lists are comma-separated
+        value: String,
+        /// Write ~/.config/pman/config.toml instead of the workspace config
+        #[arg(long)]
+        user: bool,
+    },
+    /// Print every setting with its effective value and source
+    List,
+}
+
+#[derive(Subcommand)]
+enum McpCommands {
+    /// Serve MCP tools over stdio (JSON-RPC)
+    Serve {
+        /// Override Notes root directory
+        #[arg(long)]
+        notes_dir: Option<PathBuf>,
+    },
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let Cli {
+        command,
+        format,
+        vault,
+        verbose,
+    } = Cli::parse();
+    set_verbose(verbose);
+    if format.is_some() && !supports_format(&command) {
+        bail!(
+            "--format only applies to list, new, archive, unarchive, status, show, adr list, read, patch, search, meta get, config get/list, vaults, where, doctor, wc and verify"
+        );
+    }
+    if let Some(name) = &vault {
+        select_vault(name)?;
+    }
 
-    match cli.command {
+    match command {
         Commands::Init { path } => {
             let workspace = if path.is_absolute() {
                 path
@@ -213,8 +605,17 @@ fn main() -> Result<()> {
             } else {
                 std::env::current_dir()?.join(path)
             };
-            let ok = verify_workspace(&workspace)?;
-            if !ok {
+            let report = verify_report(&workspace)?;
+            print!("{}", render_verify(&report, format));
+            if !report.ok() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Doctor { fix, notes_dir } => {
+            let root = resolve_notes_dir(notes_dir)?;
+            let report = doctor(&NotesPaths::from_root(root), fix)?;
+            print!("{}", render_doctor(&report, format));
+            if !report.ok() {
                 std::process::exit(1);
             }
         }
@@ -222,20 +623,88 @@ fn main() -> Result<()> {
             name,
             status,
             area,
+            template,
+            vars,
             notes_dir,
         } => {
             let root = resolve_notes_dir(notes_dir)?;
             let paths = NotesPaths::from_root(root);
-            let note = create_project(&paths, &name, &status, area.as_deref())?;
-            println!("Created {}", note.display());
+            let template = template.map(|name| ProjectTemplate { name, vars });
+            let status = match status {
+                Some(status) => status,
+                None => default_project_status()?,
+            };
+            let note = create_project_with_template(
+                &paths,
+                &name,
+                &status,
+                area.as_deref(),
+                template.as_ref(),
+            )?;
+            match format {
+                Some(format) => {
+                    let dir_name = dir_name_of(note.parent().unwrap_or(&note))?;
+                    let record = project_record(&paths, dir_name)?;
+                    print!("{}", render_project(&record, format));
+                }
+                None => println!("Created {}", note.display()),
+            }
         }
         Commands::Archive { project, notes_dir } => {
             let root = resolve_notes_dir(notes_dir)?;
             let paths = NotesPaths::from_root(root);
             let dest = archive_project(&paths, &project)?;
-            println!("Archived {}", dest.display());
+            match format {
+                Some(format) => {
+                    let record = project_record(&paths, dir_name_of(&dest)?)?;
+                    print!("{}", render_project(&record, format));
+                }
+                None => println!("Archived {}", dest.display()),
+            }
         }
-        Commands::List { status, notes_dir } => {
+        Commands::Unarchive {
+            project,
+            status,
+            notes_dir,
+        } => {
+            let root = resolve_notes_dir(notes_dir)?;
+            let paths = NotesPaths::from_root(root);
+            let transitions = StatusTransitions::configured()?;
+            let dest = unarchive_project(&paths, &project, &status, &transitions)?;
+            match format {
+                Some(format) => {
+                    let record = project_record(&paths, dir_name_of(&dest)?)?;
+                    print!("{}", render_project(&record, format));
+                }
+                None => println!("Unarchived {}", dest.display()),
+            }
+        }
+        Commands::Status {
+            project,
+            status,
+            force,
+            notes_dir,
+        } => {
+            let root = resolve_notes_dir(notes_dir)?;
+            let paths = NotesPaths::from_root(root);
+            let transitions = StatusTransitions::configured()?;
+            let change = set_project_status(&paths, &project, &status, &transitions, force)?;
+            match format {
+                Some(format) => {
+                    let record = project_records(&paths, None)?
+                        .into_iter()
+                        .find(|record| record.id == change.id)
+                        .context("Registry entry disappeared after status update")?;
+                    print!("{}", render_project(&record, format));
+                }
+                None => println!("Updated {}: {} -> {}", change.id, change.from, change.to),
+            }
+        }
+        Commands::List {
+            status,
+            filters,
+            notes_dir,
+        } => {
             let root = resolve_notes_dir(notes_dir)?;
             let paths = NotesPaths::from_root(root);
             let filter = if status.eq_ignore_ascii_case("all") {
@@ -243,101 +712,434 @@ fn main() -> Result<()> {
             } else {
                 Some(status.as_str())
             };
-            let output = list_projects(&paths, filter)?;
-            print!("{output}");
+            let records = filter_projects_by_meta(project_records(&paths, filter)?, &filters);
+            print!("{}", render_projects(&records, format));
+        }
+        Commands::Show { project, notes_dir } => {
+            let root = resolve_notes_dir(notes_dir)?;
+            let summary = show_project(&NotesPaths::from_root(root), &project)?;
+            print!("{}", render_show(&summary, format));
         }
+        Commands::Note { command } => match command {
+            NoteCommands::Add {
+                project,
+                name,
+                template,
+                vars,
+                dated,
+                notes_dir,
+            } => {
+                let paths = NotesPaths::from_root(resolve_notes_dir(notes_dir)?);
+                let note =
+                    add_project_note(&paths, &project, &name, template.as_deref(), &vars, dated)?;
+                println!("Created {}", note.display());
+            }
+        },
+        Commands::Attach {
+            project,
+            file,
+            dest,
+            force,
+            notes_dir,
+        } => {
+            let paths = NotesPaths::from_root(resolve_notes_dir(notes_dir)?);
+            let target = attach_file(&paths, &project, &file, dest.as_deref(), force)?;
+            println!("Attached {}", target.display());
+        }
+        Commands::Adr { command } => match command {
+            AdrCommands::New {
+                project,
+                title,
+                status,
+                supersedes,
+                notes_dir,
+            } => {
+                let paths = NotesPaths::from_root(resolve_notes_dir(notes_dir)?);
+                let adr = new_adr(&paths, &project, &title, &status, supersedes)?;
+                println!("Created {}", adr.display());
+            }
+            AdrCommands::List { project, notes_dir } => {
+                let paths = NotesPaths::from_root(resolve_notes_dir(notes_dir)?);
+                let adrs = list_adrs(&paths, project.as_deref())?;
+                print!("{}", render_adrs(&adrs, format));
+            }
+        },
         Commands::Read {
             path,
+            project,
             notes_dir,
             lines,
             numbered,
+            section,
+            outline,
+            revision,
         } => {
-            let output = read_note(notes_dir, &path, lines.as_deref(), numbered)?;
-            print!("{output}");
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
+            if outline {
+                let headings = note_outline(notes_dir, &path)?;
+                print!("{}", render_outline(&headings, format));
+            } else if revision || format == Some(OutputFormat::Json) {
+                let (output, token) = read_note_with_revision(
+                    notes_dir,
+                    &path,
+                    lines.as_deref(),
+                    section.as_deref(),
+                    numbered,
+                )?;
+                if format == Some(OutputFormat::Json) {
+                    print!("{}", render_note_json(&path, &output, &token));
+                } else {
+                    print!("{output}");
+                    eprintln!("revision: {token}");
+                }
+            } else {
+                let output = read_note(
+                    notes_dir,
+                    &path,
+                    lines.as_deref(),
+                    section.as_deref(),
+                    numbered,
+                )?;
+                print!("{output}");
+            }
         }
         Commands::Write {
             path,
+            project,
             notes_dir,
             create_dirs,
             content,
+            if_match,
         } => {
-            let body = match content {
-                Some(value) => value,
-                None => {
-                    let mut buffer = String::new();
-                    std::io::stdin().read_to_string(&mut buffer)?;
-                    buffer
-                }
-            };
-            let target = write_note(notes_dir, &path, &body, create_dirs)?;
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
+            let body = text_or_stdin(content)?;
+            let target = exit_on_conflict(write_note(
+                notes_dir,
+                &path,
+                &body,
+                create_dirs,
+                if_match.as_deref(),
+            ))?;
             println!("Wrote {}", target.display());
         }
         Commands::Edit {
             path,
+            project,
             notes_dir,
             replace_lines,
             with_text,
             expect,
+            find,
+            replace,
+            regex,
+            all,
+            expect_count,
+            if_match,
         } => {
-            let target = edit_note(
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
+            if let (Some(find), Some(replace)) = (find, replace) {
+                let options = ReplaceOptions {
+                    regex,
+                    all,
+                    expect_count,
+                };
+                let (target, replaced) = exit_on_conflict(replace_in_note(
+                    notes_dir,
+                    &path,
+                    &find,
+                    &replace,
+                    options,
+                    if_match.as_deref(),
+                ))?;
+                println!("Edited {} ({replaced} replacement(s))", target.display());
+            } else {
+                let (Some(replace_lines), Some(with_text)) = (replace_lines, with_text) else {
+                    bail!("edit requires --replace-lines with --with, or --find with --replace");
+                };
+                let target = exit_on_conflict(edit_note(
+                    notes_dir,
+                    &path,
+                    &replace_lines,
+                    &with_text,
+                    expect.as_deref(),
+                    if_match.as_deref(),
+                ))?;
+                println!("Edited {}", target.display());
+            }
+        }
+        Commands::Patch {
+            path,
+            notes_dir,
+            diff,
+            fuzz,
+            if_match,
+        } => {
+            let diff = text_or_stdin(diff)?;
+            let report = exit_on_conflict(patch_note(
                 notes_dir,
                 &path,
-                &replace_lines,
-                &with_text,
-                expect.as_deref(),
-            )?;
-            println!("Edited {}", target.display());
+                &diff,
+                fuzz,
+                if_match.as_deref(),
+            ))?;
+            print!("{}", render_patch(&report, format));
+            if !report.ok() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Append {
+            path,
+            notes_dir,
+            content,
+            section,
+            if_match,
+        } => {
+            let text = text_or_stdin(content)?;
+            let target = exit_on_conflict(append_note(
+                notes_dir,
+                &path,
+                &text,
+                section.as_deref(),
+                if_match.as_deref(),
+            ))?;
+            println!("Appended to {}", target.display());
         }
-        Commands::Cat { path, notes_dir } => {
+        Commands::Insert {
+            path,
+            notes_dir,
+            after_line,
+            content,
+            if_match,
+        } => {
+            let text = text_or_stdin(content)?;
+            let target = exit_on_conflict(insert_note(
+                notes_dir,
+                &path,
+                after_line,
+                &text,
+                if_match.as_deref(),
+            ))?;
+            println!("Inserted into {}", target.display());
+        }
+        Commands::Meta { command } => match command {
+            MetaCommands::Get {
+                path,
+                key,
+                notes_dir,
+            } => {
+                let frontmatter = read_note_meta(notes_dir, &path)?;
+                print!("{}", render_meta(&frontmatter, key.as_deref(), format)?);
+            }
+            MetaCommands::Set {
+                path,
+                key,
+                value,
+                notes_dir,
+            } => {
+                let target = set_note_meta(notes_dir, &path, &key, &value)?;
+                println!("Updated {}", target.display());
+            }
+        },
+        Commands::Search {
+            pattern,
+            notes_dir,
+            literal,
+            ignore_case,
+            folder,
+            project,
+            context,
+        } => {
+            let options = SearchOptions {
+                literal,
+                ignore_case,
+                folder,
+                project,
+                context,
+            };
+            let matches = search_notes(notes_dir, &pattern, &options)?;
+            print!("{}", render_search(&matches, format));
+            if matches.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Cat {
+            path,
+            project,
+            notes_dir,
+        } => {
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
             let output = cat_note(notes_dir, &path)?;
             print!("{output}");
         }
         Commands::Head {
             path,
+            project,
             lines,
             notes_dir,
         } => {
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
             let output = head_note(notes_dir, &path, lines)?;
             print!("{output}");
         }
         Commands::Tail {
             path,
+            project,
             lines,
             notes_dir,
         } => {
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
             let output = tail_note(notes_dir, &path, lines)?;
             print!("{output}");
         }
         Commands::Wc {
             path,
+            project,
             notes_dir,
             lines,
             words,
             bytes,
             chars,
         } => {
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
             let flags = WcFlags {
                 lines,
                 words,
                 bytes,
                 chars,
             };
-            let output = wc_note(notes_dir, &path, flags)?;
-            print!("{output}");
+            let counts = wc_counts(notes_dir, &path)?;
+            print!("{}", render_wc(&counts, flags, format));
         }
-        Commands::Less { path, notes_dir } => {
+        Commands::Less {
+            path,
+            project,
+            notes_dir,
+        } => {
+            let path = scoped_path(&notes_dir, path, project.as_deref())?;
             if let Some(output) = less_note(notes_dir, &path)? {
                 print!("{output}");
             }
         }
+        Commands::Open {
+            target,
+            print_path,
+            notes_dir,
+        } => {
+            if print_path {
+                println!("{}", resolve_open_target(notes_dir, &target)?.display());
+            } else {
+                let (path, changed) = open_note(notes_dir, &target)?;
+                if changed {
+                    println!("Edited {}", path.display());
+                }
+            }
+        }
+        Commands::Vaults => {
+            let active = active_vault()?;
+            print!("{}", render_vaults(&vaults()?, active.as_deref(), format));
+        }
+        Commands::Where { notes_dir } => {
+            print!("{}", render_where(&where_report(notes_dir)?, format));
+        }
+        Commands::Config { command } => match command {
+            ConfigCommands::Get { key } => {
+                let entry = config_get(&key)?;
+                match format {
+                    Some(format) => print!("{}", render_config(&[entry], Some(format))),
+                    None => println!("{}", entry.value.as_deref().unwrap_or("")),
+                }
+            }
+            ConfigCommands::Set { key, value, user } => {
+                let path = config_set(&key, &value, user)?;
+                println!("Updated {}", path.display());
+            }
+            ConfigCommands::List => print!("{}", render_config(&config_list()?, format)),
+        },
         Commands::Skill { command } => match command {
             SkillCommands::Generate { profile } => {
                 let output = generate_skill(&profile)?;
                 print!("{output}");
             }
         },
+        Commands::Mcp { command } => match command {
+            McpCommands::Serve { notes_dir } => {
+                let server = McpServer::new(notes_dir);
+                server.serve(std::io::stdin().lock(), std::io::stdout().lock())?;
+            }
+        },
     }
 
     Ok(())
 }
 
+/// Whether `command` renders `--format`; the rest print a human message only.
+fn supports_format(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::List { .. }
+            | Commands::New { .. }
+            | Commands::Archive { .. }
+            | Commands::Unarchive { .. }
+            | Commands::Status { .. }
+            | Commands::Show { .. }
+            | Commands::Adr {
+                command: AdrCommands::List { .. }
+            }
+            | Commands::Read { .. }
+            | Commands::Patch { .. }
+            | Commands::Search { .. }
+            | Commands::Meta {
+                command: MetaCommands::Get { .. }
+            }
+            | Commands::Config {
+                command: ConfigCommands::Get { .. } | ConfigCommands::List
+            }
+            | Commands::Vaults
+            | Commands::Where { .. }
+            | Commands::Doctor { .. }
+            | Commands::Wc { .. }
+            | Commands::Verify { .. }
+    )
+}
+
+/// The note path for `--project`-aware commands, relative to the Notes root.
+fn scoped_path(
+    notes_dir: &Option<PathBuf>,
+    path: Option<PathBuf>,
+    project: Option<&str>,
+) -> Result<PathBuf> {
+    match project {
+        Some(project) => project_note_path(notes_dir.clone(), project, path.as_deref()),
+        None => path.context("A note path is required without --project"),
+    }
+}
+
+fn text_or_stdin(value: Option<String>) -> Result<String> {
+    match value {
+        Some(value) => Ok(value),
+        None => {
+            let mut buffer = String::new();
+            std::io::stdin().read_to_string(&mut buffer)?;
+            Ok(buffer)
+        }
+    }
+}
+
+/// `--if-match` conflicts exit with this status so scripts can re-read and retry.
+const CONFLICT_EXIT_CODE: i32 = 3;
+
+fn exit_on_conflict<T>(result: Result<T>) -> Result<T> {
+    if let Err(err) = &result
+        && let Some(mismatch) = err.downcast_ref::<RevisionMismatch>()
+    {
+        eprintln!("Error: {mismatch}");
+        std::process::exit(CONFLICT_EXIT_CODE);
+    }
+    result
+}
+
+fn dir_name_of(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .context("Project directory name is not valid UTF-8")
+}
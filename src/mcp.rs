@@ -0,0 +1,912 @@
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value, json};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    DEFAULT_PATCH_FUZZ, MetaFilter, NotesPaths, ProjectTemplate, ReplaceOptions, SearchOptions,
+    StatusTransitions, TemplateVar, append_note, archive_project, create_project_with_template,
+    default_project_status, edit_note, filter_projects_by_meta, insert_note, note_outline,
+    patch_note, project_note_path, project_records, read_note, read_note_meta,
+    read_note_with_revision, render_meta, render_note_json, render_outline, render_patch,
+    render_projects, render_search, render_show, replace_in_note, resolve_notes_dir, search_notes,
+    set_note_meta, set_project_status, show_project, unarchive_project, write_note,
+};
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const SERVER_NAME: &str = "pman";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// MCP server that speaks newline-delimited JSON-RPC 2.0 and calls the
+/// note/project operations in-process.
+pub struct McpServer {
+    notes_dir: Option<PathBuf>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl McpServer {
+    pub fn new(notes_dir: Option<PathBuf>) -> Self {
+        Self { notes_dir }
+    }
+
+    /// Serve requests from `input` until EOF, writing one response per line to `output`.
+    pub fn serve<R: BufRead, W: Write>(&self, input: R, mut output: W) -> Result<()> {
+        for line in input.lines() {
+            let line = line.context("Failed to read MCP request")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(output, "{response}").context("Failed to write MCP response")?;
+                output.flush().context("Failed to flush MCP response")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one JSON-RPC message. Notifications produce no response.
+    pub fn handle_line(&self, line: &str) -> Option<Value> {
+        let message = match serde_json::from_str::<Value>(line) {
+            Ok(value) => value,
+            Err(err) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError {
+                        code: PARSE_ERROR,
+                        message: format!("Parse error: {err}"),
+                    },
+                ));
+            }
+        };
+        self.handle_message(&message)
+    }
+
+    fn handle_message(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses from the client carry no method and need no reply.
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError {
+                    code: INVALID_REQUEST,
+                    message: "Invalid request: missing method".to_string(),
+                },
+            ));
+        };
+
+        // Notifications (no id) are acknowledged silently.
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err(RpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Method not found: {method}"),
+            }),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> std::result::Result<Value, RpcError> {
+        let Some(name) = params.get("name").and_then(Value::as_str) else {
+            return Err(RpcError {
+                code: INVALID_PARAMS,
+                message: "tools/call requires a tool name".to_string(),
+            });
+        };
+        let empty = Map::new();
+        let arguments = match params.get("arguments") {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(map)) => map,
+            Some(_) => {
+                return Err(RpcError {
+                    code: INVALID_PARAMS,
+                    message: "tools/call arguments must be an object".to_string(),
+                });
+            }
+        };
+
+        let outcome = match name {
+            "notes_read" => self.notes_read(arguments),
+            "notes_write" => self.notes_write(arguments),
+            "notes_edit" => self.notes_edit(arguments),
+            "notes_replace" => self.notes_replace(arguments),
+            "notes_patch" => self.notes_patch(arguments),
+            "notes_append" => self.notes_append(arguments),
+            "notes_insert" => self.notes_insert(arguments),
+            "notes_search" => self.notes_search(arguments),
+            "notes_meta_get" => self.notes_meta_get(arguments),
+            "notes_meta_set" => self.notes_meta_set(arguments),
+            "project_list" => self.project_list(arguments),
+            "project_show" => self.project_show(arguments),
+            "project_new" => self.project_new(arguments),
+            "project_archive" => self.project_archive(arguments),
+            "project_unarchive" => self.project_unarchive(arguments),
+            "project_status" => self.project_status(arguments),
+            _ => {
+                return Err(RpcError {
+                    code: INVALID_PARAMS,
+                    message: format!("Unknown tool: {name}"),
+                });
+            }
+        };
+
+        Ok(match outcome {
+            Ok(text) => json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": false,
+            }),
+            Err(err) => json!({
+                "content": [{ "type": "text", "text": format!("{err:#}") }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn notes_paths(&self) -> Result<NotesPaths> {
+        let root = resolve_notes_dir(self.notes_dir.clone())?;
+        Ok(NotesPaths::from_root(root))
+    }
+
+    /// `path`, or with `project` a path inside that project's directory
+    /// (default: its README.md).
+    fn note_path(&self, args: &Map<String, Value>) -> Result<PathBuf> {
+        let path = optional_str(args, "path")?;
+        match optional_str(args, "project")? {
+            Some(project) => {
+                project_note_path(self.notes_dir.clone(), project, path.map(Path::new))
+            }
+            None => Ok(PathBuf::from(required_str(args, "path")?)),
+        }
+    }
+
+    fn notes_read(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        if optional_bool(args, "outline")?.unwrap_or(false) {
+            let headings = note_outline(self.notes_dir.clone(), &path)?;
+            return Ok(render_outline(&headings, None));
+        }
+        let lines = optional_str(args, "lines")?;
+        let section = optional_str(args, "section")?;
+        let numbered = optional_bool(args, "numbered")?.unwrap_or(false);
+        if !optional_bool(args, "revision")?.unwrap_or(false) {
+            return read_note(self.notes_dir.clone(), &path, lines, section, numbered);
+        }
+        let (content, revision) =
+            read_note_with_revision(self.notes_dir.clone(), &path, lines, section, numbered)?;
+        Ok(render_note_json(&path, &content, &revision))
+    }
+
+    fn notes_write(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let content = required_str(args, "content")?;
+        let create_dirs = optional_bool(args, "create_dirs")?.unwrap_or(false);
+        let if_match = optional_str(args, "if_match")?;
+        let target = write_note(
+            self.notes_dir.clone(),
+            &path,
+            content,
+            create_dirs,
+            if_match,
+        )?;
+        Ok(format!("Wrote {}\n", target.display()))
+    }
+
+    fn notes_edit(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let replace_lines = required_str(args, "replace_lines")?;
+        let with_text = required_str(args, "with_text")?;
+        let expect = optional_str(args, "expect")?;
+        let if_match = optional_str(args, "if_match")?;
+        let target = edit_note(
+            self.notes_dir.clone(),
+            &path,
+            replace_lines,
+            with_text,
+            expect,
+            if_match,
+        )?;
+        Ok(format!("Edited {}\n", target.display()))
+    }
+
+    fn notes_replace(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let find = required_str(args, "find")?;
+        let replace = required_str(args, "replace")?;
+        let options = ReplaceOptions {
+            regex: optional_bool(args, "regex")?.unwrap_or(false),
+            all: optional_bool(args, "all")?.unwrap_or(false),
+            expect_count: optional_u64(args, "expect_count")?.map(|count| count as usize),
+        };
+        let if_match = optional_str(args, "if_match")?;
+        let (target, replaced) = replace_in_note(
+            self.notes_dir.clone(),
+            &path,
+            find,
+            replace,
+            options,
+            if_match,
+        )?;
+        Ok(format!(
+            "Edited {} ({replaced} replacement(s))\n",
+            target.display()
+        ))
+    }
+
+    fn notes_patch(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let diff = required_str(args, "diff")?;
+        let fuzz = optional_u64(args, "fuzz")?.map_or(DEFAULT_PATCH_FUZZ, |fuzz| fuzz as usize);
+        let if_match = optional_str(args, "if_match")?;
+        let report = patch_note(self.notes_dir.clone(), &path, diff, fuzz, if_match)?;
+        let output = render_patch(&report, None);
+        if !report.ok() {
+            bail!("{}", output.trim_end());
+        }
+        Ok(output)
+    }
+
+    fn notes_append(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let content = required_str(args, "content")?;
+        let section = optional_str(args, "section")?;
+        let if_match = optional_str(args, "if_match")?;
+        let target = append_note(self.notes_dir.clone(), &path, content, section, if_match)?;
+        Ok(format!("Appended to {}\n", target.display()))
+    }
+
+    fn notes_insert(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let Some(after_line) = optional_u64(args, "after_line")? else {
+            bail!("Missing required argument: after_line");
+        };
+        let content = required_str(args, "content")?;
+        let if_match = optional_str(args, "if_match")?;
+        let target = insert_note(
+            self.notes_dir.clone(),
+            &path,
+            after_line as usize,
+            content,
+            if_match,
+        )?;
+        Ok(format!("Inserted into {}\n", target.display()))
+    }
+
+    fn notes_search(&self, args: &Map<String, Value>) -> Result<String> {
+        let pattern = required_str(args, "pattern")?;
+        let options = SearchOptions {
+            literal: optional_bool(args, "literal")?.unwrap_or(false),
+            ignore_case: optional_bool(args, "ignore_case")?.unwrap_or(false),
+            folder: optional_str(args, "folder")?.map(str::to_string),
+            project: optional_str(args, "project")?.map(str::to_string),
+            context: optional_u64(args, "context")?.unwrap_or(0) as usize,
+        };
+        let matches = search_notes(self.notes_dir.clone(), pattern, &options)?;
+        if matches.is_empty() {
+            return Ok("No matches found.\n".to_string());
+        }
+        Ok(render_search(&matches, None))
+    }
+
+    fn notes_meta_get(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let key = optional_str(args, "key")?;
+        let frontmatter = read_note_meta(self.notes_dir.clone(), &path)?;
+        render_meta(&frontmatter, key, None)
+    }
+
+    fn notes_meta_set(&self, args: &Map<String, Value>) -> Result<String> {
+        let path = self.note_path(args)?;
+        let key = required_str(args, "key")?;
+        let value = required_str(args, "value")?;
+        let target = set_note_meta(self.notes_dir.clone(), &path, key, value)?;
+        Ok(format!("Updated {}\n", target.display()))
+    }
+
+    fn project_list(&self, args: &Map<String, Value>) -> Result<String> {
+        let status = optional_str(args, "status")?.unwrap_or("active");
+        let filter = if status.eq_ignore_ascii_case("all") {
+            None
+        } else {
+            Some(status)
+        };
+        let filters = optional_string_map(args, "where")?
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}").parse::<MetaFilter>())
+            .collect::<Result<Vec<MetaFilter>>>()?;
+        let records = project_records(&self.notes_paths()?, filter)?;
+        Ok(render_projects(
+            &filter_projects_by_meta(records, &filters),
+            None,
+        ))
+    }
+
+    fn project_show(&self, args: &Map<String, Value>) -> Result<String> {
+        let project = required_str(args, "project")?;
+        let summary = show_project(&self.notes_paths()?, project)?;
+        Ok(render_show(&summary, None))
+    }
+
+    fn project_new(&self, args: &Map<String, Value>) -> Result<String> {
+        let name = required_str(args, "name")?;
+        let status = match optional_str(args, "status")? {
+            Some(status) => status.to_string(),
+            None => default_project_status()?,
+        };
+        let area = optional_str(args, "area")?;
+        let vars = optional_string_map(args, "vars")?
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}").parse::<TemplateVar>())
+            .collect::<Result<Vec<TemplateVar>>>()?;
+        let template = match optional_str(args, "template")? {
+            Some(name) => Some(ProjectTemplate {
+                name: name.to_string(),
+                vars,
+            }),
+            None if !vars.is_empty() => bail!("Argument vars requires template"),
+            None => None,
+        };
+        let note = create_project_with_template(
+            &self.notes_paths()?,
+            name,
+            &status,
+            area,
+            template.as_ref(),
+        )?;
+        Ok(format!("Created {}\n", note.display()))
+    }
+
+    fn project_archive(&self, args: &Map<String, Value>) -> Result<String> {
+        let project = required_str(args, "project")?;
+        let dest = archive_project(&self.notes_paths()?, project)?;
+        Ok(format!("Archived {}\n", dest.display()))
+    }
+
+    fn project_unarchive(&self, args: &Map<String, Value>) -> Result<String> {
+        let project = required_str(args, "project")?;
+        let status = optional_str(args, "status")?.unwrap_or("active");
+        let transitions = StatusTransitions::configured()?;
+        let dest = unarchive_project(&self.notes_paths()?, project, status, &transitions)?;
+        Ok(format!("Unarchived {}\n", dest.display()))
+    }
+
+    fn project_status(&self, args: &Map<String, Value>) -> Result<String> {
+        let project = required_str(args, "project")?;
+        let status = required_str(args, "status")?;
+        let force = optional_bool(args, "force")?.unwrap_or(false);
+        let transitions = StatusTransitions::configured()?;
+        let change =
+            set_project_status(&self.notes_paths()?, project, status, &transitions, force)?;
+        Ok(format!(
+            "Updated {}: {} -> {}\n",
+            change.id, change.from, change.to
+        ))
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    // Echo the client's protocol version when offered; otherwise advertise ours.
+    let protocol_version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "notes_read",
+            "description": "Read a note file from the Notes directory. Returns the file contents.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "lines": { "type": "string", "description": "Inclusive line range (start:end), 1-based" },
+                    "section": { "type": "string", "description": "Read only this heading section, e.g. \"## Next\" (instead of lines)" },
+                    "outline": { "type": "boolean", "description": "Return the note's headings with line numbers instead of its contents" },
+                    "numbered": { "type": "boolean", "description": "Include line numbers in output" },
+                    "revision": { "type": "boolean", "description": "Return JSON with path, revision and content; pass the revision as if_match when writing" }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "notes_write",
+            "description": "Write or replace a note file's full contents.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "content": { "type": "string", "description": "Full note contents" },
+                    "create_dirs": { "type": "boolean", "description": "Create parent directories if missing" },
+                    "if_match": { "type": "string", "description": "Only write if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["content"]
+            }
+        },
+        {
+            "name": "notes_edit",
+            "description": "Edit a note file by replacing an inclusive line range. Supports an optional expected-text guard to detect stale edits.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "replace_lines": { "type": "string", "description": "Inclusive line range to replace (start:end), 1-based" },
+                    "with_text": { "type": "string", "description": "Replacement text for the selected range" },
+                    "expect": { "type": "string", "description": "Expected current text of the range" },
+                    "if_match": { "type": "string", "description": "Only edit if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["replace_lines", "with_text"]
+            }
+        },
+        {
+            "name": "notes_replace",
+            "description": "Edit a note by search and replace instead of line numbers. Replaces the first match, or all with all=true; expect_count refuses the edit unless the note has exactly that many matches.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "find": { "type": "string", "description": "Text to find (literal unless regex is true)" },
+                    "replace": { "type": "string", "description": "Replacement text; $1 and ${name} expand when regex is true" },
+                    "regex": { "type": "boolean", "description": "Treat find as a regex" },
+                    "all": { "type": "boolean", "description": "Replace every match instead of only the first" },
+                    "expect_count": { "type": "integer", "minimum": 0, "description": "Required number of matches in the note" },
+                    "if_match": { "type": "string", "description": "Only edit if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["find", "replace"]
+            }
+        },
+        {
+            "name": "notes_patch",
+            "description": "Apply a unified diff (diff -u) to one note. Hunks are matched by context, so several non-contiguous edits apply in one call without recomputing line numbers. Nothing is written if any hunk is rejected.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "diff": { "type": "string", "description": "Unified diff for this note" },
+                    "fuzz": { "type": "integer", "minimum": 0, "description": "Context lines a hunk may ignore at each end (default 2)" },
+                    "if_match": { "type": "string", "description": "Only patch if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["diff"]
+            }
+        },
+        {
+            "name": "notes_append",
+            "description": "Append lines to the end of a note, or to the end of a markdown heading section such as \"## Notes\" (the usual way to log progress into a project note).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "content": { "type": "string", "description": "Lines to append" },
+                    "section": { "type": "string", "description": "Heading to append under, e.g. \"## Notes\" or \"Next\"" },
+                    "if_match": { "type": "string", "description": "Only append if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["content"]
+            }
+        },
+        {
+            "name": "notes_insert",
+            "description": "Insert lines after a given line of a note (0 inserts at the top).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "after_line": { "type": "integer", "minimum": 0, "description": "1-based line to insert after" },
+                    "content": { "type": "string", "description": "Lines to insert" },
+                    "if_match": { "type": "string", "description": "Only insert if the note still has this revision (from notes_read with revision)" }
+                },
+                "required": ["after_line", "content"]
+            }
+        },
+        {
+            "name": "notes_search",
+            "description": "Search note contents under the Notes root. Returns path:line:text matches.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Regex pattern, or literal text when literal is true" },
+                    "literal": { "type": "boolean", "description": "Treat the pattern as literal text" },
+                    "ignore_case": { "type": "boolean", "description": "Match case-insensitively" },
+                    "folder": { "type": "string", "description": "Limit to Projects, Areas, Resources or Archives" },
+                    "project": { "type": "string", "description": "Limit to one project (id, directory prefix, slug or name)" },
+                    "context": { "type": "integer", "minimum": 0, "description": "Lines of context around each match" }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "notes_meta_get",
+            "description": "Read frontmatter from a note. Returns one value, or key: value lines when no key is given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "key": { "type": "string", "description": "Frontmatter key" }
+                },
+                "required": []
+            }
+        },
+        {
+            "name": "notes_meta_set",
+            "description": "Set a frontmatter key on a note, adding the block if missing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Note path relative to notes root, or to the project directory with project" },
+                    "project": { "type": "string", "description": "Project id, slug or name; path is then relative to its directory and defaults to README.md" },
+                    "key": { "type": "string", "description": "Frontmatter key" },
+                    "value": { "type": "string", "description": "Single-line value" }
+                },
+                "required": ["key", "value"]
+            }
+        },
+        {
+            "name": "project_list",
+            "description": "List projects from the registry. Defaults to active projects.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string", "description": "Status filter, or 'all'" },
+                    "where": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Frontmatter filters, e.g. {\"area\": \"payments\"}"
+                    }
+                }
+            }
+        },
+        {
+            "name": "project_show",
+            "description": "Summarize one project: registry row, frontmatter, created date and age, last modified time, files, and the Summary and Next sections of its README.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project id, directory prefix, slug or name" }
+                },
+                "required": ["project"]
+            }
+        },
+        {
+            "name": "project_new",
+            "description": "Create a new project note in Notes/Projects.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Project name or explicit directory name" },
+                    "status": { "type": "string", "description": "Status label (default: default_status from the config, else draft)" },
+                    "area": { "type": "string", "description": "Area slug to prefix the project slug" },
+                    "template": { "type": "string", "description": "README template name from Notes/Resources/Templates/ or .pman/templates/" },
+                    "vars": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Extra template placeholder values"
+                    }
+                },
+                "required": ["name"]
+            }
+        },
+        {
+            "name": "project_archive",
+            "description": "Archive a project by moving it to Notes/Archives/Projects.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project id, directory prefix, slug or name" }
+                },
+                "required": ["project"]
+            }
+        },
+        {
+            "name": "project_unarchive",
+            "description": "Restore an archived project into Notes/Projects and set its status.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Archived project id, directory prefix, slug or name" },
+                    "status": { "type": "string", "description": "Status for the restored project, one of the configured statuses (default: active)" }
+                },
+                "required": ["project"]
+            }
+        },
+        {
+            "name": "project_status",
+            "description": "Move a project to a new status, updating the registry and the README frontmatter together.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project id, directory prefix, slug or name" },
+                    "status": { "type": "string", "description": "New status" },
+                    "force": { "type": "boolean", "description": "Allow transitions outside the configured set" }
+                },
+                "required": ["project", "status"]
+            }
+        }
+    ])
+}
+
+fn required_str<'a>(args: &'a Map<String, Value>, key: &str) -> Result<&'a str> {
+    match optional_str(args, key)? {
+        Some(value) => Ok(value),
+        None => bail!("Missing required argument: {key}"),
+    }
+}
+
+fn optional_str<'a>(args: &'a Map<String, Value>, key: &str) -> Result<Option<&'a str>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.as_str())),
+        Some(_) => bail!("Argument {key} must be a string"),
+    }
+}
+
+/// An optional object of string values, e.g. `{"area": "payments"}`.
+fn optional_string_map(args: &Map<String, Value>, key: &str) -> Result<Vec<(String, String)>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(name, value)| match value.as_str() {
+                Some(value) => Ok((name.clone(), value.to_string())),
+                None => bail!("Argument {key}.{name} must be a string"),
+            })
+            .collect(),
+        Some(_) => bail!("Argument {key} must be an object"),
+    }
+}
+
+fn optional_u64(args: &Map<String, Value>, key: &str) -> Result<Option<u64>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => match value.as_u64() {
+            Some(number) => Ok(Some(number)),
+            None => bail!("Argument {key} must be a non-negative integer"),
+        },
+    }
+}
+
+fn optional_bool(args: &Map<String, Value>, key: &str) -> Result<Option<bool>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(_) => bail!("Argument {key} must be a boolean"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn call(server: &McpServer, request: Value) -> Value {
+        server
+            .handle_line(&request.to_string())
+            .expect("request should produce a response")
+    }
+
+    #[test]
+    fn initialize_advertises_tools_capability() {
+        let server = McpServer::new(None);
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": { "protocolVersion": "2025-03-26", "capabilities": {} }
+            }),
+        );
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert!(response["result"]["capabilities"]["tools"].is_object());
+        assert_eq!(response["result"]["serverInfo"]["name"], "pman");
+    }
+
+    #[test]
+    fn notifications_and_unknown_methods() {
+        let server = McpServer::new(None);
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle_line(&notification.to_string()).is_none());
+
+        let response = call(
+            &server,
+            json!({ "jsonrpc": "2.0", "id": "a", "method": "resources/list" }),
+        );
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server.handle_line("{not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn tools_list_exposes_core_tools() {
+        let server = McpServer::new(None);
+        let response = call(
+            &server,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        );
+        let names = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            names,
+            vec![
+                "notes_read",
+                "notes_write",
+                "notes_edit",
+                "notes_replace",
+                "notes_patch",
+                "notes_append",
+                "notes_insert",
+                "notes_search",
+                "notes_meta_get",
+                "notes_meta_set",
+                "project_list",
+                "project_show",
+                "project_new",
+                "project_archive",
+                "project_unarchive",
+                "project_status"
+            ]
+        );
+    }
+
+    #[test]
+    fn tools_call_round_trips_notes_and_projects() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("Notes");
+        fs::create_dir_all(root.join("Projects")).unwrap();
+        let server = McpServer::new(Some(root.clone()));
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {
+                    "name": "notes_write",
+                    "arguments": { "path": "Resources/a.md", "content": "one\ntwo\n", "create_dirs": true }
+                }
+            }),
+        );
+        assert_eq!(response["result"]["isError"], false);
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 4,
+                "method": "tools/call",
+                "params": {
+                    "name": "notes_read",
+                    "arguments": { "path": "Resources/a.md", "lines": "2:2" }
+                }
+            }),
+        );
+        assert_eq!(response["result"]["content"][0]["text"], "two\n");
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 5,
+                "method": "tools/call",
+                "params": { "name": "project_new", "arguments": { "name": "mcp-1-demo" } }
+            }),
+        );
+        assert_eq!(response["result"]["isError"], false);
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 6,
+                "method": "tools/call",
+                "params": { "name": "project_list", "arguments": { "status": "draft" } }
+            }),
+        );
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("MCP-1\tdraft\tmcp-1-demo"));
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "tools/call",
+                "params": {
+                    "name": "notes_meta_set",
+                    "arguments": { "project": "MCP-1", "key": "owner", "value": "ana" }
+                }
+            }),
+        );
+        assert_eq!(response["result"]["isError"], false);
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "tools/call",
+                "params": {
+                    "name": "notes_meta_get",
+                    "arguments": { "project": "mcp-1", "key": "owner" }
+                }
+            }),
+        );
+        assert_eq!(response["result"]["content"][0]["text"], "ana\n");
+    }
+
+    #[test]
+    fn tools_call_reports_failures_as_tool_errors() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("Notes");
+        fs::create_dir_all(&root).unwrap();
+        let server = McpServer::new(Some(root));
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "tools/call",
+                "params": { "name": "notes_read", "arguments": { "path": "../outside.md" } }
+            }),
+        );
+        assert_eq!(response["result"]["isError"], true);
+
+        let response = call(
+            &server,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "tools/call",
+                "params": { "name": "notes_delete", "arguments": {} }
+            }),
+        );
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::atomic::write_atomic;
+use crate::lock::lock_notes;
+use crate::{Frontmatter, NotesPaths, resolve_project};
+
+/// Resolve `target` to a note file: a path relative to the Notes root, or a
+/// project (see [`resolve_project`]) whose main note is used.
+pub fn resolve_open_target(notes_dir: Option<PathBuf>, target: &str) -> Result<PathBuf> {
+    let root = crate::canonical_notes_root(notes_dir)?;
+    open_target(&root, target)
+}
+
+/// Open a note in `$VISUAL` or `$EDITOR` and wait for the editor to exit.
+///
+/// When the editor changed the note and it has a frontmatter block, its
+/// `updated:` field is set to today. Returns the note path and whether the
+/// note changed.
+pub fn open_note(notes_dir: Option<PathBuf>, target: &str) -> Result<(PathBuf, bool)> {
+    let root = crate::canonical_notes_root(notes_dir)?;
+    let path = open_target(&root, target)?;
+    let editor = editor_command()?;
+    let changed = edit_with(&root, &path, &editor)?;
+    Ok((path, changed))
+}
+
+fn open_target(root: &Path, target: &str) -> Result<PathBuf> {
+    let relative = Path::new(target);
+    if relative.is_absolute() || root.join(relative).exists() {
+        let joined = root.join(relative);
+        if joined.is_dir() {
+            return crate::resolve_existing_note_file(root, &relative.join("README.md"));
+        }
+        return crate::resolve_existing_note_file(root, relative);
+    }
+
+    let paths = NotesPaths::from_root(root.to_path_buf());
+    let project = resolve_project(&paths, target)
+        .with_context(|| format!("{target} is neither a note path nor a project"))?;
+    let note = project.note(&paths);
+    let relative = note.strip_prefix(root).unwrap_or(&note);
+    crate::resolve_existing_note_file(root, relative)
+}
+
+/// `$VISUAL`, then `$EDITOR`; the first non-empty one wins.
+fn editor_command() -> Result<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .context("No editor configured; set $VISUAL or $EDITOR, or use --print-path")
+}
+
+/// Run `editor` on `path`, then bump `updated:` if the note changed.
+fn edit_with(root: &Path, path: &Path, editor: &str) -> Result<bool> {
+    let before =
+        fs::read(path).with_context(|| format!("Failed to read note {}", path.display()))?;
+
+    let status = shell_process(editor, path)
+        .status()
+        .with_context(|| format!("Failed to run editor {editor}"))?;
+    if !status.success() {
+        bail!("Editor {editor} exited with non-zero status");
+    }
+
+    let _lock = lock_notes(root)?;
+    let after = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read note {}", path.display()))?;
+    if after.as_bytes() == before.as_slice() {
+        return Ok(false);
+    }
+    let mut frontmatter = Frontmatter::parse(&after);
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    if frontmatter.has_block() && frontmatter.get("updated").as_deref() != Some(today.as_str()) {
+        frontmatter.set("updated", &today);
+        write_atomic(path, frontmatter.to_string())
+            .with_context(|| format!("Failed to write note {}", path.display()))?;
+    }
+    Ok(true)
+}
+
+/// Run a configured command (editor, pager) on `path` through the shell, as
+/// git does, so quoted paths and arguments
+/// (`"/Applications/Sublime Text.app/.../subl" -w`) work.
+pub(crate) fn shell_process(program: &str, path: &Path) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command
+            .arg("/C")
+            .arg(format!("{program} \"{}\"", path.display()));
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{program} \"$@\""))
+            .arg(program)
+            .arg(path);
+        command
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::REGISTRY_HEADER;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    fn editor_script(dir: &Path, body: &str) -> String {
+        let dir = dir.join("my editor");
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("editor.sh");
+        fs::write(&script, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        format!("'{}' --wait", script.display())
+    }
+
+    #[test]
+    fn opens_projects_and_bumps_updated_after_edits() {
+        let temp = tempdir().unwrap();
+        let root = fs::canonicalize(temp.path()).unwrap();
+        let paths = NotesPaths::from_root(root.clone());
+        let dir = paths.projects_dir.join("proj-5-payment-retry");
+        fs::create_dir_all(&dir).unwrap();
+        let note = dir.join("README.md");
+        fs::write(
+            &note,
+            "---\nstatus: active\n---\n\n# PROJ-5: Payment Retry\n",
+        )
+        .unwrap();
+        fs::write(
+            &paths.registry,
+            format!(
+                "{REGISTRY_HEADER}| PROJ-5 | Payment Retry | active | 2026-01-05 | [proj-5-payment-retry/README.md](proj-5-payment-retry/README.md) |\n"
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(open_target(&root, "PROJ-5").unwrap(), note);
+        assert_eq!(open_target(&root, "payment-retry").unwrap(), note);
+        assert_eq!(
+            open_target(&root, "Projects/proj-5-payment-retry").unwrap(),
+            note
+        );
+        assert!(open_target(&root, "../outside.md").is_err());
+
+        assert!(!edit_with(&root, &note, "true").unwrap());
+        assert!(!fs::read_to_string(&note).unwrap().contains("updated:"));
+
+        let editor = editor_script(
+            temp.path(),
+            r#"[ "$1" = --wait ] || exit 1; echo "- edited" >> "$2""#,
+        );
+        assert!(edit_with(&root, &note, &editor).unwrap());
+        let content = fs::read_to_string(&note).unwrap();
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        assert!(content.starts_with(&format!("---\nstatus: active\nupdated: {today}\n---\n")));
+        assert!(content.ends_with("- edited\n"));
+    }
+}
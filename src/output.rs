@@ -0,0 +1,95 @@
+use anyhow::{Error, bail};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Machine-readable output formats selected with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Tsv,
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            "table" => Ok(Self::Table),
+            _ => bail!("Unknown format {value}; supported formats: json, tsv, table"),
+        }
+    }
+}
+
+pub(crate) fn render_json(value: &Value) -> String {
+    let mut output = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    output.push('\n');
+    output
+}
+
+pub(crate) fn render_tsv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|row| row.join("\t") + "\n")
+        .collect::<String>()
+}
+
+/// Left-aligned columns separated by two spaces, with an uppercase header row.
+pub(crate) fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect::<Vec<usize>>();
+    for row in rows {
+        for (index, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(index) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let header_row = headers
+        .iter()
+        .map(|header| header.to_ascii_uppercase())
+        .collect::<Vec<String>>();
+    let mut output = String::new();
+    for row in std::iter::once(&header_row).chain(rows.iter()) {
+        let line = row
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let width = widths.get(index).copied().unwrap_or_default();
+                format!("{cell:<width$}")
+            })
+            .collect::<Vec<String>>()
+            .join("  ");
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_formats_case_insensitively() {
+        assert_eq!("JSON".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert_eq!("tsv".parse::<OutputFormat>().unwrap(), OutputFormat::Tsv);
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn render_table_aligns_columns() {
+        let rows = vec![
+            vec!["PROJ-1".to_string(), "active".to_string()],
+            vec!["PROJ-10".to_string(), "done".to_string()],
+        ];
+        assert_eq!(
+            render_table(&["id", "status"], &rows),
+            "ID       STATUS\nPROJ-1   active\nPROJ-10  done\n"
+        );
+    }
+}
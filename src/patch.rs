@@ -0,0 +1,481 @@
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::atomic::write_atomic;
+use crate::lock::lock_notes;
+use crate::output::{render_json, render_table, render_tsv};
+use crate::revision::check_revision;
+use crate::{
+    OutputFormat, canonical_notes_root, join_lines, resolve_existing_note_file, split_lines,
+};
+
+/// Context lines `pman patch` may ignore at each end of a hunk by default,
+/// matching GNU patch.
+pub const DEFAULT_PATCH_FUZZ: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// One `@@ -a,b +c,d @@` section of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    header: String,
+    old_start: usize,
+    lines: Vec<HunkLine>,
+    /// `\ No newline at end of file` after the old / new side's last line.
+    old_missing_newline: bool,
+    new_missing_newline: bool,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+
+    /// Leading and trailing context line counts, the lines fuzz may drop.
+    fn context_edges(&self) -> (usize, usize) {
+        let is_context = |line: &&HunkLine| matches!(line, HunkLine::Context(_));
+        let leading = self.lines.iter().take_while(is_context).count();
+        let trailing = if leading == self.lines.len() {
+            0
+        } else {
+            self.lines.iter().rev().take_while(is_context).count()
+        };
+        (leading, trailing)
+    }
+}
+
+/// How one hunk fared; `line` is where it landed in the patched note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkOutcome {
+    pub header: String,
+    pub applied: bool,
+    /// 1-based line in the patched note (applied hunks only).
+    pub line: Option<usize>,
+    /// Distance from the line the diff expected, in lines.
+    pub offset: isize,
+    /// Context lines ignored at each end to make the hunk match.
+    pub fuzz: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchReport {
+    pub path: PathBuf,
+    pub hunks: Vec<HunkOutcome>,
+}
+
+impl PatchReport {
+    /// True when every hunk applied and the note was written.
+    pub fn ok(&self) -> bool {
+        self.hunks.iter().all(|hunk| hunk.applied)
+    }
+
+    fn rejected(&self) -> usize {
+        self.hunks.iter().filter(|hunk| !hunk.applied).count()
+    }
+}
+
+/// Apply a single-file unified diff to a note.
+///
+/// Hunks are located by their context, searching outward from the expected
+/// line, and may drop up to `fuzz` context lines at each end. The note is only
+/// written when every hunk applies; otherwise the report lists the rejects.
+pub fn patch_note(
+    notes_dir: Option<PathBuf>,
+    path: &Path,
+    diff: &str,
+    fuzz: usize,
+    if_match: Option<&str>,
+) -> Result<PatchReport> {
+    let hunks = parse_unified_diff(diff)?;
+    let root = canonical_notes_root(notes_dir)?;
+    let _lock = lock_notes(&root)?;
+    let target = resolve_existing_note_file(&root, path)?;
+    let content = fs::read_to_string(&target)
+        .with_context(|| format!("Failed to read note {}", target.display()))?;
+    if let Some(expected) = if_match {
+        check_revision(&target, expected, Some(content.as_bytes()))?;
+    }
+
+    let (mut lines, mut trailing_newline) = split_lines(&content);
+    let outcomes = apply_hunks(&hunks, &mut lines, &mut trailing_newline, fuzz);
+    let report = PatchReport {
+        path: path.to_path_buf(),
+        hunks: outcomes,
+    };
+    if report.ok() {
+        write_atomic(&target, join_lines(&lines, trailing_newline))
+            .with_context(|| format!("Failed to write note {}", target.display()))?;
+    }
+    Ok(report)
+}
+
+fn parse_unified_diff(diff: &str) -> Result<Vec<Hunk>> {
+    let header_re = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@")
+        .expect("valid hunk header regex");
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut files = 0;
+    let mut input = diff
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .peekable();
+
+    while let Some(line) = input.next() {
+        if line.starts_with("+++ ") {
+            files += 1;
+            if files > 1 {
+                bail!("Patch touches more than one file; pass one note's diff at a time");
+            }
+            continue;
+        }
+        let Some(captures) = header_re.captures(line) else {
+            // `diff`, `index`, `---` and other preamble lines.
+            continue;
+        };
+        let count = |index: usize| {
+            captures
+                .get(index)
+                .map_or(Ok(1), |value| value.as_str().parse::<usize>())
+        };
+        let old_start = captures[1].parse::<usize>()?;
+        let (mut old_left, mut new_left) = (count(2)?, count(4)?);
+
+        let mut hunk = Hunk {
+            header: captures[0].to_string(),
+            old_start,
+            lines: Vec::new(),
+            old_missing_newline: false,
+            new_missing_newline: false,
+        };
+        while old_left > 0 || new_left > 0 {
+            let Some(body) = input.next() else {
+                bail!("Hunk {} ends before its line counts", hunk.header);
+            };
+            // Some editors strip the single space from blank context lines.
+            let (marker, text) = match body.as_bytes().first() {
+                Some(&marker) => (marker as char, body.get(1..).unwrap_or("")),
+                None => (' ', ""),
+            };
+            match marker {
+                ' ' if old_left > 0 && new_left > 0 => {
+                    old_left -= 1;
+                    new_left -= 1;
+                    hunk.lines.push(HunkLine::Context(text.to_string()));
+                }
+                '-' if old_left > 0 => {
+                    old_left -= 1;
+                    hunk.lines.push(HunkLine::Remove(text.to_string()));
+                }
+                '+' if new_left > 0 => {
+                    new_left -= 1;
+                    hunk.lines.push(HunkLine::Add(text.to_string()));
+                }
+                '\\' => mark_missing_newline(&mut hunk),
+                _ => bail!("Malformed hunk {}: unexpected line {body:?}", hunk.header),
+            }
+        }
+        // The marker for the hunk's last line comes after the counts run out.
+        while input.next_if(|next| next.starts_with('\\')).is_some() {
+            mark_missing_newline(&mut hunk);
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        bail!("Patch contains no hunks; expected a unified diff (diff -u)");
+    }
+    Ok(hunks)
+}
+
+fn mark_missing_newline(hunk: &mut Hunk) {
+    match hunk.lines.last() {
+        Some(HunkLine::Remove(_)) => hunk.old_missing_newline = true,
+        Some(HunkLine::Add(_)) => hunk.new_missing_newline = true,
+        Some(HunkLine::Context(_)) => {
+            hunk.old_missing_newline = true;
+            hunk.new_missing_newline = true;
+        }
+        None => {}
+    }
+}
+
+fn apply_hunks(
+    hunks: &[Hunk],
+    lines: &mut Vec<String>,
+    trailing_newline: &mut bool,
+    max_fuzz: usize,
+) -> Vec<HunkOutcome> {
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    // Net lines added so far, plus the drift of the last hunk, like GNU patch.
+    let mut shift: isize = 0;
+    // Hunks apply in order, so never match above the previous one.
+    let mut floor = 0;
+
+    for hunk in hunks {
+        let old = hunk.old_lines();
+        let new = hunk.new_lines();
+        let expected = if old.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = (expected as isize + shift).max(0) as usize;
+        let (leading, trailing) = hunk.context_edges();
+
+        let found = (0..=max_fuzz).find_map(|fuzz| {
+            let front = fuzz.min(leading);
+            let back = fuzz.min(trailing);
+            // Fuzz may drop context, never the lines being changed.
+            if fuzz > 0 && front + back == 0 {
+                return None;
+            }
+            let block = &old[front..old.len() - back];
+            find_block(lines, block, expected + front, floor)
+                .map(|start| (fuzz, start - front, front, back))
+        });
+
+        let Some((fuzz, start, front, back)) = found else {
+            outcomes.push(HunkOutcome {
+                header: hunk.header.clone(),
+                applied: false,
+                line: None,
+                offset: 0,
+                fuzz: 0,
+            });
+            continue;
+        };
+
+        let replaced_start = start + front;
+        let replaced_end = replaced_start + old.len() - front - back;
+        let reaches_end = replaced_end == lines.len();
+        let replacement = new[front..new.len() - back]
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>();
+        let inserted = replacement.len();
+        lines.splice(replaced_start..replaced_end, replacement);
+        if reaches_end && back == 0 {
+            if hunk.new_missing_newline {
+                *trailing_newline = false;
+            } else if hunk.old_missing_newline {
+                *trailing_newline = true;
+            }
+        }
+
+        let offset = start as isize - expected as isize;
+        shift += offset + inserted as isize - (replaced_end - replaced_start) as isize;
+        floor = replaced_start + inserted;
+        outcomes.push(HunkOutcome {
+            header: hunk.header.clone(),
+            applied: true,
+            line: Some(start + 1),
+            offset,
+            fuzz,
+        });
+    }
+    outcomes
+}
+
+/// Nearest start index at or after `floor` where `block` matches `lines`.
+fn find_block(lines: &[String], block: &[&str], expected: usize, floor: usize) -> Option<usize> {
+    let last = lines.len().checked_sub(block.len())?;
+    if floor > last {
+        return None;
+    }
+    let expected = expected.clamp(floor, last);
+    let matches_at = |start: usize| {
+        lines[start..start + block.len()]
+            .iter()
+            .zip(block)
+            .all(|(line, wanted)| line == wanted)
+    };
+    (0..=last - floor).find_map(|distance| {
+        [
+            expected
+                .checked_add(distance)
+                .filter(|start| *start <= last),
+            expected
+                .checked_sub(distance)
+                .filter(|start| distance > 0 && *start >= floor),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|start| matches_at(*start))
+    })
+}
+
+pub fn render_patch(report: &PatchReport, format: Option<OutputFormat>) -> String {
+    let status = |hunk: &HunkOutcome| if hunk.applied { "applied" } else { "rejected" };
+    let rows = report
+        .hunks
+        .iter()
+        .enumerate()
+        .map(|(index, hunk)| {
+            vec![
+                (index + 1).to_string(),
+                hunk.header.clone(),
+                status(hunk).to_string(),
+                hunk.line.map(|line| line.to_string()).unwrap_or_default(),
+                hunk.offset.to_string(),
+                hunk.fuzz.to_string(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    match format {
+        None => {
+            let mut output = String::new();
+            for (index, hunk) in report.hunks.iter().enumerate() {
+                if !hunk.applied {
+                    output.push_str(&format!(
+                        "Hunk #{} {} rejected: context not found\n",
+                        index + 1,
+                        hunk.header
+                    ));
+                } else if hunk.offset != 0 || hunk.fuzz > 0 {
+                    output.push_str(&format!(
+                        "Hunk #{} applied at line {} (offset {}, fuzz {})\n",
+                        index + 1,
+                        hunk.line.unwrap_or_default(),
+                        hunk.offset,
+                        hunk.fuzz
+                    ));
+                }
+            }
+            if report.ok() {
+                output.push_str(&format!("Patched {}\n", report.path.display()));
+            } else {
+                output.push_str(&format!(
+                    "{} of {} hunk(s) rejected; {} left unchanged\n",
+                    report.rejected(),
+                    report.hunks.len(),
+                    report.path.display()
+                ));
+            }
+            output
+        }
+        Some(OutputFormat::Json) => render_json(&json!({
+            "path": report.path,
+            "ok": report.ok(),
+            "hunks": report
+                .hunks
+                .iter()
+                .map(|hunk| json!({
+                    "header": hunk.header,
+                    "status": status(hunk),
+                    "line": hunk.line,
+                    "offset": hunk.offset,
+                    "fuzz": hunk.fuzz,
+                }))
+                .collect::<Vec<_>>(),
+        })),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => render_table(
+            &["hunk", "header", "status", "line", "offset", "fuzz"],
+            &rows,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(content: &str, diff: &str, fuzz: usize) -> (String, Vec<HunkOutcome>) {
+        let hunks = parse_unified_diff(diff).unwrap();
+        let (mut lines, mut trailing) = split_lines(content);
+        let outcomes = apply_hunks(&hunks, &mut lines, &mut trailing, fuzz);
+        (join_lines(&lines, trailing), outcomes)
+    }
+
+    #[test]
+    fn applies_non_contiguous_hunks_with_offset() {
+        let content = "# Title\nintro\n\n## Tasks\n- a\n- b\n\n## Notes\nold note\nend\n";
+        // Line numbers are off by two, as if taken from an older copy.
+        let diff = "--- a/README.md\n+++ b/README.md\n\
+@@ -1,3 +1,3 @@\n # Title\n-intro\n+summary\n \n\
+@@ -5,2 +5,3 @@\n - a\n+- a2\n - b\n\
+@@ -10,2 +11,2 @@\n-old note\n+new note\n end\n";
+        let (patched, outcomes) = apply(content, diff, 0);
+        assert_eq!(
+            patched,
+            "# Title\nsummary\n\n## Tasks\n- a\n- a2\n- b\n\n## Notes\nnew note\nend\n"
+        );
+        assert!(outcomes.iter().all(|hunk| hunk.applied));
+        assert_eq!(outcomes[2].offset, -1);
+        assert_eq!(outcomes[2].line, Some(10));
+    }
+
+    #[test]
+    fn fuzz_ignores_stale_outer_context() {
+        let content = "one\ntwo (edited)\nthree\nfour\n";
+        let diff = "@@ -1,4 +1,4 @@\n one\n two\n-three\n+3\n four\n";
+        let (_, outcomes) = apply(content, diff, 0);
+        assert!(!outcomes[0].applied);
+
+        let (patched, outcomes) = apply(content, diff, 2);
+        assert_eq!(patched, "one\ntwo (edited)\n3\nfour\n");
+        assert_eq!(outcomes[0].fuzz, 2);
+    }
+
+    #[test]
+    fn handles_missing_newline_markers() {
+        let diff = "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n";
+        let (patched, _) = apply("a\nb", diff, 0);
+        assert_eq!(patched, "a\nc\n");
+
+        let diff = "@@ -1 +1 @@\n-a\n+b\n\\ No newline at end of file\n";
+        let (patched, _) = apply("a\n", diff, 0);
+        assert_eq!(patched, "b");
+    }
+
+    #[test]
+    fn rejected_hunk_leaves_note_unchanged() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("Notes");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("note.md"), "a\nb\nc\n").unwrap();
+        let diff = "@@ -1 +1 @@\n-a\n+A\n@@ -3 +3 @@\n-missing\n+C\n";
+
+        let report = patch_note(Some(root.clone()), Path::new("note.md"), diff, 2, None).unwrap();
+        assert!(!report.ok());
+        assert!(report.hunks[0].applied);
+        assert!(!report.hunks[1].applied);
+        assert!(render_patch(&report, None).contains("Hunk #2 @@ -3 +3 @@ rejected"));
+        assert_eq!(
+            fs::read_to_string(root.join("note.md")).unwrap(),
+            "a\nb\nc\n"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_and_multi_file_diffs() {
+        assert!(parse_unified_diff("just text\n").is_err());
+        assert!(parse_unified_diff("@@ -1,2 +1,2 @@\n a\n").is_err());
+        let two_files = "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n--- a/y\n+++ b/y\n";
+        assert!(parse_unified_diff(two_files).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::atomic::write_atomic;
+
+/// One project row of `_registry.md`.
+///
+/// Cell values are stored unescaped; `note_link` holds the raw Note cell
+/// (normally a `[path](path)` markdown link). Columns beyond the first five
+/// are kept verbatim in `extra`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub created: String,
+    pub note_link: String,
+    pub extra: Vec<String>,
+}
+
+impl RegistryEntry {
+    pub fn new(id: &str, name: &str, status: &str, created: &str, note_path: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            status: status.to_string(),
+            created: created.to_string(),
+            note_link: Self::link_for(note_path),
+            extra: Vec::new(),
+        }
+    }
+
+    /// Markdown link cell for a note path relative to `Projects/`.
+    pub fn link_for(note_path: &str) -> String {
+        format!("[{note_path}]({note_path})")
+    }
+
+    /// Link target of the Note cell, or the bare cell text when it is not a link.
+    pub fn note_path(&self) -> &str {
+        let cell = self.note_link.trim();
+        let Some(link_start) = cell.find("](") else {
+            return cell;
+        };
+        let rest = &cell[(link_start + 2)..];
+        match rest.find(')') {
+            Some(link_end) => &rest[..link_end],
+            None => cell,
+        }
+    }
+
+    /// Project directory name the Note cell points at.
+    pub fn dir_name(&self) -> Option<&str> {
+        let target = self.note_path();
+        let trimmed = target.trim_start_matches("./");
+        let trimmed = trimmed.strip_suffix("/README.md").unwrap_or(trimmed);
+        let trimmed = trimmed.trim_end_matches('/');
+        let name = trimmed.rsplit('/').next().unwrap_or_default();
+        if name.is_empty() { None } else { Some(name) }
+    }
+
+    fn cells(&self) -> Vec<&str> {
+        let mut cells = vec![
+            self.id.as_str(),
+            self.name.as_str(),
+            self.status.as_str(),
+            self.created.as_str(),
+            self.note_link.as_str(),
+        ];
+        cells.extend(self.extra.iter().map(String::as_str));
+        cells
+    }
+
+    fn render(&self) -> String {
+        let cells = self
+            .cells()
+            .into_iter()
+            .map(escape_cell)
+            .collect::<Vec<String>>();
+        format!("| {} |", cells.join(" | "))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum RegistryLine {
+    Text(String),
+    Entry {
+        entry: RegistryEntry,
+        original: Option<String>,
+    },
+}
+
+/// Parsed `_registry.md`.
+///
+/// Everything that is not a project row (preamble, table header, separator,
+/// comments, trailing text) is preserved as-is, and rows that were not
+/// modified are written back byte-for-byte.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    lines: Vec<RegistryLine>,
+    trailing_newline: bool,
+}
+
+impl Registry {
+    pub fn parse(contents: &str) -> Self {
+        let raw_lines = contents.lines().collect::<Vec<&str>>();
+        let mut lines = Vec::with_capacity(raw_lines.len());
+        let mut in_comment = false;
+
+        for (index, line) in raw_lines.iter().enumerate() {
+            if in_comment {
+                in_comment = !line.contains("-->");
+                lines.push(RegistryLine::Text(line.to_string()));
+                continue;
+            }
+            if let Some(start) = line.find("<!--") {
+                in_comment = !line[start..].contains("-->");
+                lines.push(RegistryLine::Text(line.to_string()));
+                continue;
+            }
+
+            let is_header = raw_lines
+                .get(index + 1)
+                .is_some_and(|next| is_separator_row(next));
+            let entry = if is_header || is_separator_row(line) {
+                None
+            } else {
+                parse_entry(line)
+            };
+
+            match entry {
+                Some(entry) => lines.push(RegistryLine::Entry {
+                    original: Some(line.to_string()),
+                    entry,
+                }),
+                None => lines.push(RegistryLine::Text(line.to_string())),
+            }
+        }
+
+        Self {
+            lines,
+            trailing_newline: contents.is_empty() || contents.ends_with('\n'),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read registry at {}", path.display()))?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, self.to_string())
+            .with_context(|| format!("Failed to write registry {}", path.display()))
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &RegistryEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            RegistryLine::Entry { entry, .. } => Some(entry),
+            RegistryLine::Text(_) => None,
+        })
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut RegistryEntry> {
+        self.lines.iter_mut().filter_map(|line| match line {
+            RegistryLine::Entry { entry, .. } => Some(entry),
+            RegistryLine::Text(_) => None,
+        })
+    }
+
+    /// Look up an entry by registry id (case-insensitive).
+    pub fn find(&self, id: &str) -> Option<&RegistryEntry> {
+        self.entries()
+            .find(|entry| entry.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut RegistryEntry> {
+        self.entries_mut()
+            .find(|entry| entry.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Look up an entry by the project directory its Note cell links to.
+    pub fn find_by_dir_name(&self, dir_name: &str) -> Option<&RegistryEntry> {
+        self.entries()
+            .find(|entry| entry.dir_name() == Some(dir_name))
+    }
+
+    pub fn find_by_dir_name_mut(&mut self, dir_name: &str) -> Option<&mut RegistryEntry> {
+        self.entries_mut()
+            .find(|entry| entry.dir_name() == Some(dir_name))
+    }
+
+    /// Append an entry after the last table row.
+    pub fn push(&mut self, entry: RegistryEntry) {
+        let insert_at = self
+            .lines
+            .iter()
+            .rposition(|line| match line {
+                RegistryLine::Entry { .. } => true,
+                RegistryLine::Text(text) => is_separator_row(text),
+            })
+            .map(|index| index + 1)
+            .unwrap_or(self.lines.len());
+        self.lines.insert(
+            insert_at,
+            RegistryLine::Entry {
+                entry,
+                original: None,
+            },
+        );
+        if insert_at == self.lines.len() - 1 {
+            self.trailing_newline = true;
+        }
+    }
+
+    /// Next numeric id for `PROJ-<n>` style ids.
+    pub fn next_id(&self, prefix: &str) -> u32 {
+        let marker = format!("{prefix}-");
+        self.entries()
+            .filter_map(|entry| {
+                let rest = entry.id.strip_prefix(&marker)?;
+                rest.parse::<u32>().ok()
+            })
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.lines.len();
+        for (index, line) in self.lines.iter().enumerate() {
+            match line {
+                RegistryLine::Text(text) => f.write_str(text)?,
+                RegistryLine::Entry { entry, original } => match original {
+                    Some(raw) if parse_entry(raw).as_ref() == Some(entry) => f.write_str(raw)?,
+                    _ => f.write_str(&entry.render())?,
+                },
+            }
+            if index + 1 < count || self.trailing_newline {
+                f.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_entry(line: &str) -> Option<RegistryEntry> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('|') {
+        return None;
+    }
+
+    let mut cells = split_cells(trimmed);
+    if cells.len() < 5 {
+        return None;
+    }
+    let extra = cells.split_off(5);
+    let mut cells = cells.into_iter();
+    Some(RegistryEntry {
+        id: cells.next().unwrap_or_default(),
+        name: cells.next().unwrap_or_default(),
+        status: cells.next().unwrap_or_default(),
+        created: cells.next().unwrap_or_default(),
+        note_link: cells.next().unwrap_or_default(),
+        extra,
+    })
+}
+
+/// Split a `| a | b |` row on unescaped pipes, unescaping `\|` in cells.
+fn split_cells(row: &str) -> Vec<String> {
+    let inner = row.strip_prefix('|').unwrap_or(row);
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = inner.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                current.push('|');
+                chars.next();
+            }
+            '|' => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(ch),
+        }
+    }
+    if !current.trim().is_empty() {
+        cells.push(current.trim().to_string());
+    }
+    cells
+}
+
+fn escape_cell(value: &str) -> String {
+    value.replace(['\r', '\n'], " ").replace('|', "\\|")
+}
+
+fn is_separator_row(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with('|')
+        && trimmed.contains('-')
+        && trimmed
+            .chars()
+            .all(|ch| matches!(ch, '|' | '-' | ':' | ' ' | '\t'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Project Registry\n\nSome preamble text.\n\n| ID | Name | Status | Created | Note | Owner |\n| --- | --- | --- | --- | --- | --- |\n| PROJ-1 | One | active | 2026-01-01 | [proj-1-one/README.md](proj-1-one/README.md) | ana |\n<!-- | PROJ-9 | Commented | active | 2026-01-01 | [x](x) | -->\n|PROJ-2|Two  |archived|2026-01-02|[../Archives/Projects/proj-2-two/README.md](../Archives/Projects/proj-2-two/README.md)|bo|\n\nTrailing notes.\n";
+
+    #[test]
+    fn parse_and_render_round_trip_losslessly() {
+        let registry = Registry::parse(SAMPLE);
+        assert_eq!(registry.to_string(), SAMPLE);
+
+        let ids = registry
+            .entries()
+            .map(|entry| entry.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["PROJ-1", "PROJ-2"]);
+
+        let two = registry.find("proj-2").unwrap();
+        assert_eq!(two.name, "Two");
+        assert_eq!(two.extra, vec!["bo".to_string()]);
+        assert_eq!(two.dir_name(), Some("proj-2-two"));
+    }
+
+    #[test]
+    fn modified_rows_are_rerendered_and_others_preserved() {
+        let mut registry = Registry::parse(SAMPLE);
+        registry.find_mut("PROJ-2").unwrap().status = "active".to_string();
+
+        let rendered = registry.to_string();
+        assert!(rendered.contains("| PROJ-2 | Two | active | 2026-01-02 |"));
+        assert!(rendered.contains("| bo |"));
+        assert!(rendered.contains("| PROJ-1 | One | active | 2026-01-01 |"));
+        assert!(rendered.contains("<!-- | PROJ-9 |"));
+        assert!(rendered.ends_with("\nTrailing notes.\n"));
+    }
+
+    #[test]
+    fn pipes_in_names_are_escaped() {
+        let mut registry = Registry::parse(SAMPLE);
+        registry.push(RegistryEntry::new(
+            "PROJ-3",
+            "A | B",
+            "draft",
+            "2026-01-03",
+            "proj-3-a-b/README.md",
+        ));
+
+        let rendered = registry.to_string();
+        assert!(rendered.contains("| PROJ-3 | A \\| B | draft |"));
+        let reparsed = Registry::parse(&rendered);
+        assert_eq!(reparsed.find("PROJ-3").unwrap().name, "A | B");
+        assert_eq!(
+            reparsed.find_by_dir_name("proj-3-a-b").unwrap().id,
+            "PROJ-3"
+        );
+    }
+
+    #[test]
+    fn push_appends_after_last_row() {
+        let mut registry = Registry::parse(SAMPLE);
+        registry.push(RegistryEntry::new(
+            "PROJ-3",
+            "Three",
+            "draft",
+            "2026-01-03",
+            "proj-3-three/README.md",
+        ));
+
+        let rendered = registry.to_string();
+        let row = rendered.find("| PROJ-3 |").unwrap();
+        assert!(row > rendered.find("|PROJ-2|").unwrap());
+        assert!(row < rendered.find("Trailing notes.").unwrap());
+
+        let mut empty = Registry::parse(crate::REGISTRY_HEADER);
+        empty.push(RegistryEntry::new(
+            "PROJ-1",
+            "One",
+            "draft",
+            "2026-01-01",
+            "proj-1-one/README.md",
+        ));
+        assert!(empty.to_string().ends_with(
+            "| --- | --- | --- | --- | --- |\n| PROJ-1 | One | draft | 2026-01-01 | [proj-1-one/README.md](proj-1-one/README.md) |\n"
+        ));
+    }
+
+    #[test]
+    fn next_id_ignores_comments_and_foreign_ids() {
+        let mut registry = Registry::parse(SAMPLE);
+        registry.push(RegistryEntry::new(
+            "MYSLUG-1192",
+            "myslug-1192-mythingy",
+            "active",
+            "2026-01-03",
+            "myslug-1192-mythingy/README.md",
+        ));
+        assert_eq!(registry.next_id("PROJ"), 3);
+    }
+}
@@ -0,0 +1,358 @@
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{NotesPaths, Registry, RegistryEntry};
+
+/// A project directory found by [`resolve_project`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedProject {
+    /// Registry id, or the id implied by the directory name when unregistered.
+    pub id: String,
+    pub name: String,
+    pub dir: PathBuf,
+    pub dir_name: String,
+    /// True when the project lives in `Archives/Projects/`.
+    pub archived: bool,
+    pub entry: Option<RegistryEntry>,
+}
+
+impl ResolvedProject {
+    /// The project's main note: the registry link, or `README.md` in its directory.
+    pub fn note(&self, paths: &NotesPaths) -> PathBuf {
+        match &self.entry {
+            Some(entry) => crate::normalize_path(&paths.projects_dir.join(entry.note_path())),
+            None => self.dir.join("README.md"),
+        }
+    }
+
+    fn label(&self) -> String {
+        format!(
+            "{} {} ({}, {})",
+            self.id,
+            self.name,
+            self.dir_name,
+            if self.archived { "archived" } else { "active" }
+        )
+    }
+}
+
+/// Find one project in `Projects/` or `Archives/Projects/`.
+///
+/// `input` may be a registry id (`PROJ-12`), a directory name or prefix
+/// (`proj-12`), a slug (`payment-retry`) or words from the project name
+/// (`payment retry`). Matching stops at the first of those tiers with any
+/// hits; more than one hit lists the candidates instead of guessing. With no
+/// hits, projects within a few typos of `input` are suggested, never picked.
+pub fn resolve_project(paths: &NotesPaths, input: &str) -> Result<ResolvedProject> {
+    let input = input.trim();
+    if input.is_empty() {
+        bail!("Project must not be empty");
+    }
+    let candidates = project_candidates(paths)?;
+    let wanted = input.to_ascii_lowercase();
+    let words = wanted
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>();
+    let wanted_slug = words.join("-");
+
+    let tiers: [&dyn Fn(&ResolvedProject) -> bool; 4] = [
+        &|project| project.id.eq_ignore_ascii_case(input) || project.dir_name == wanted,
+        &|project| project.dir_name.starts_with(&format!("{wanted}-")),
+        &|project| {
+            let slug = dir_slug(&project.dir_name);
+            !wanted_slug.is_empty()
+                && (slug == wanted_slug || slug.ends_with(&format!("-{wanted_slug}")))
+        },
+        &|project| {
+            let haystack = format!(
+                "{} {}",
+                project.name.to_ascii_lowercase(),
+                dir_slug(&project.dir_name)
+            );
+            !words.is_empty() && words.iter().all(|word| haystack.contains(word))
+        },
+    ];
+
+    for matches_tier in tiers {
+        let mut matches = candidates
+            .iter()
+            .filter(|project| matches_tier(project))
+            .collect::<Vec<&ResolvedProject>>();
+        match matches.len() {
+            0 => continue,
+            1 => return Ok(matches.remove(0).clone()),
+            _ => bail!(
+                "Multiple projects match {input}:\n{}\nUse the registry id or the full directory name",
+                matches
+                    .iter()
+                    .map(|project| format!("  {}", project.label()))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+        }
+    }
+    let suggestions = suggestions(&candidates, &wanted_slug);
+    if suggestions.is_empty() {
+        bail!("No project matching {input} in Projects/ or Archives/Projects/");
+    }
+    bail!(
+        "No project matching {input} in Projects/ or Archives/Projects/; did you mean:\n{}",
+        suggestions
+            .iter()
+            .map(|project| format!("  {}", project.label()))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+/// Projects whose id, directory name, slug or a slug word is within a third
+/// of `wanted`'s length in edits, closest first.
+fn suggestions<'a>(candidates: &'a [ResolvedProject], wanted: &str) -> Vec<&'a ResolvedProject> {
+    const MAX_SUGGESTIONS: usize = 5;
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+    let limit = wanted.chars().count().div_ceil(3);
+    let mut scored = candidates
+        .iter()
+        .filter_map(|project| {
+            let id = project.id.to_ascii_lowercase();
+            let slug = dir_slug(&project.dir_name);
+            [id.as_str(), project.dir_name.as_str(), slug]
+                .into_iter()
+                .chain(slug.split('-'))
+                .map(|key| edit_distance(wanted, key))
+                .min()
+                .filter(|distance| *distance <= limit)
+                .map(|distance| (distance, project))
+        })
+        .collect::<Vec<(usize, &ResolvedProject)>>();
+    scored.sort_by_key(|(distance, _)| *distance);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, project)| project)
+        .collect()
+}
+
+/// Levenshtein distance: single-character insertions, deletions and substitutions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Path of a note inside `project`'s directory, relative to the Notes root,
+/// for `--project`. Without `path` this is the project's main note.
+pub fn project_note_path(
+    notes_dir: Option<PathBuf>,
+    project: &str,
+    path: Option<&Path>,
+) -> Result<PathBuf> {
+    let root = crate::canonical_notes_root(notes_dir)?;
+    let paths = NotesPaths::from_root(root.clone());
+    let project = resolve_project(&paths, project)?;
+    let target = match path {
+        Some(path) => path_in_project(&project, path)?,
+        None => project.note(&paths),
+    };
+    target
+        .strip_prefix(&root)
+        .map(Path::to_path_buf)
+        .with_context(|| format!("Path escapes notes root: {}", target.display()))
+}
+
+/// `path` (relative to the project directory) joined onto it, refusing
+/// absolute paths and paths that escape the directory.
+pub(crate) fn path_in_project(project: &ResolvedProject, path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        bail!(
+            "Path must be relative to the project directory: {}",
+            path.display()
+        );
+    }
+    let target = crate::normalize_path(&project.dir.join(path));
+    if !target.starts_with(&project.dir) {
+        bail!(
+            "Path escapes project directory {}: {}",
+            project.dir_name,
+            path.display()
+        );
+    }
+    Ok(target)
+}
+
+/// Every project directory, active first, with its registry row if any.
+pub(crate) fn project_candidates(paths: &NotesPaths) -> Result<Vec<ResolvedProject>> {
+    let registry = if paths.registry.exists() {
+        Registry::load(&paths.registry)?
+    } else {
+        Registry::default()
+    };
+
+    let mut candidates = Vec::new();
+    for (base, archived) in [
+        (&paths.projects_dir, false),
+        (&paths.archives_projects_dir, true),
+    ] {
+        for dir_name in project_dir_names(base)? {
+            let entry = registry.find_by_dir_name(&dir_name).cloned();
+            let (id, name) = match &entry {
+                Some(entry) => (entry.id.clone(), entry.name.clone()),
+                None => (
+                    crate::project_id_from_dir(&dir_name).unwrap_or_else(|_| dir_name.clone()),
+                    dir_slug(&dir_name).replace('-', " "),
+                ),
+            };
+            candidates.push(ResolvedProject {
+                id,
+                name,
+                dir: base.join(&dir_name),
+                dir_name,
+                archived,
+                entry,
+            });
+        }
+    }
+    Ok(candidates)
+}
+
+/// Project directory names under `base`, skipping `.` and `_` entries
+/// (empty when `base` is missing).
+pub(crate) fn project_dir_names(base: &Path) -> Result<Vec<String>> {
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in fs::read_dir(base).with_context(|| format!("Failed to read {}", base.display()))? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str()
+            && !name.starts_with(['.', '_'])
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Slug part of `<prefix>-<number>-<slug>`, or the whole name otherwise.
+fn dir_slug(dir_name: &str) -> &str {
+    let mut parts = dir_name.splitn(3, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(_), Some(number), Some(slug)) if number.chars().all(|ch| ch.is_ascii_digit()) => slug,
+        _ => dir_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::REGISTRY_HEADER;
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, NotesPaths) {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        for dir in [
+            paths.projects_dir.join("proj-12-payment-retry"),
+            paths.projects_dir.join("proj-13-ops-payment-alerts"),
+            paths.projects_dir.join("proj-120-search"),
+            paths.archives_projects_dir.join("proj-4-old-billing"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(
+            &paths.registry,
+            format!(
+                "{REGISTRY_HEADER}| PROJ-12 | Payment Retry | active | 2026-01-01 | [proj-12-payment-retry/README.md](proj-12-payment-retry/README.md) |\n\
+| PROJ-13 | Payment Alerts | draft | 2026-01-02 | [proj-13-ops-payment-alerts/README.md](proj-13-ops-payment-alerts/README.md) |\n\
+| PROJ-4 | Old Billing | archived | 2025-01-01 | [../Archives/Projects/proj-4-old-billing/README.md](../Archives/Projects/proj-4-old-billing/README.md) |\n"
+            ),
+        )
+        .unwrap();
+        (temp, paths)
+    }
+
+    #[test]
+    fn resolves_ids_prefixes_slugs_and_names() {
+        let (_temp, paths) = setup();
+        let resolve = |input: &str| resolve_project(&paths, input).unwrap().dir_name;
+
+        assert_eq!(resolve("PROJ-12"), "proj-12-payment-retry");
+        assert_eq!(resolve("proj-12"), "proj-12-payment-retry");
+        assert_eq!(resolve("proj-120-search"), "proj-120-search");
+        assert_eq!(resolve("payment-retry"), "proj-12-payment-retry");
+        assert_eq!(resolve("payment-alerts"), "proj-13-ops-payment-alerts");
+        assert_eq!(resolve("retry"), "proj-12-payment-retry");
+
+        let archived = resolve_project(&paths, "old billing").unwrap();
+        assert!(archived.archived);
+        assert_eq!(archived.id, "PROJ-4");
+
+        let unregistered = resolve_project(&paths, "search").unwrap();
+        assert_eq!(unregistered.id, "PROJ-120");
+        assert!(unregistered.entry.is_none());
+    }
+
+    #[test]
+    fn ambiguous_and_missing_inputs_explain_themselves() {
+        let (_temp, paths) = setup();
+        let err = resolve_project(&paths, "payment").unwrap_err().to_string();
+        assert!(err.contains("Multiple projects match payment"));
+        assert!(err.contains("PROJ-12 Payment Retry (proj-12-payment-retry, active)"));
+        assert!(err.contains("PROJ-13 Payment Alerts"));
+
+        let err = resolve_project(&paths, "nothing").unwrap_err().to_string();
+        assert!(err.contains("No project matching nothing"));
+        assert!(!err.contains("did you mean"));
+
+        let err = resolve_project(&paths, "paymnet retyr")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("did you mean:\n  PROJ-12 Payment Retry (proj-12-payment-retry, active)")
+        );
+        assert!(!err.contains("PROJ-13"));
+        assert_eq!(edit_distance("billing", "biling"), 1);
+    }
+
+    #[test]
+    fn project_note_paths_stay_inside_the_project() {
+        let (temp, _paths) = setup();
+        let root = Some(temp.path().to_path_buf());
+        let note = |project: &str, path: Option<&str>| {
+            project_note_path(root.clone(), project, path.map(Path::new))
+        };
+
+        assert_eq!(
+            note("PROJ-12", None).unwrap(),
+            Path::new("Projects/proj-12-payment-retry/README.md")
+        );
+        assert_eq!(
+            note("payment-retry", Some("notes/./call.md")).unwrap(),
+            Path::new("Projects/proj-12-payment-retry/notes/call.md")
+        );
+        assert_eq!(
+            note("old billing", None).unwrap(),
+            Path::new("Archives/Projects/proj-4-old-billing/README.md")
+        );
+        let err = note("PROJ-12", Some("../proj-120-search/README.md"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("escapes project directory proj-12-payment-retry"));
+    }
+}
@@ -0,0 +1,79 @@
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Hex characters of the SHA-256 digest kept in a revision token.
+const REVISION_LEN: usize = 16;
+
+/// Revision token for note contents: a truncated SHA-256 of the exact bytes.
+pub fn note_revision(content: impl AsRef<[u8]>) -> String {
+    let digest = Sha256::digest(content.as_ref());
+    let mut token = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    token.truncate(REVISION_LEN);
+    token
+}
+
+/// Returned (inside `anyhow::Error`) when `--if-match` does not match the
+/// note on disk, so callers can tell a conflict apart from other failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionMismatch {
+    pub path: PathBuf,
+    pub expected: String,
+    /// Current revision, or `None` when the note does not exist.
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for RevisionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Revision mismatch for {}: expected {}, found {}; the note changed since it was read, re-read it and retry",
+            self.path.display(),
+            self.expected,
+            self.actual.as_deref().unwrap_or("no file")
+        )
+    }
+}
+
+impl std::error::Error for RevisionMismatch {}
+
+/// Compare an `--if-match` token against the current contents (`None` when
+/// the note does not exist yet).
+pub(crate) fn check_revision(
+    path: &std::path::Path,
+    expected: &str,
+    current: Option<&[u8]>,
+) -> Result<(), RevisionMismatch> {
+    let expected = expected.trim().to_ascii_lowercase();
+    let actual = current.map(note_revision);
+    if actual.as_deref() == Some(expected.as_str()) {
+        return Ok(());
+    }
+    Err(RevisionMismatch {
+        path: path.to_path_buf(),
+        expected,
+        actual,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn revisions_are_stable_and_content_sensitive() {
+        assert_eq!(note_revision(""), "e3b0c44298fc1c14");
+        assert_ne!(note_revision("a\n"), note_revision("a"));
+
+        let path = Path::new("note.md");
+        let revision = note_revision("body");
+        assert!(check_revision(path, &revision.to_uppercase(), Some(b"body")).is_ok());
+        let err = check_revision(path, &revision, Some(b"edited")).unwrap_err();
+        assert_eq!(err.actual, Some(note_revision("edited")));
+        assert!(check_revision(path, &revision, None).is_err());
+    }
+}
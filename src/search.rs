@@ -0,0 +1,377 @@
+use anyhow::{Context, Result, bail};
+use regex::{Regex, RegexBuilder};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::output::{render_json, render_table, render_tsv};
+use crate::{NotesPaths, OutputFormat, canonical_notes_root, ensure_contained, resolve_project};
+
+const PARA_FOLDERS: [&str; 4] = ["Projects", "Areas", "Resources", "Archives"];
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Treat the pattern as literal text instead of a regex.
+    pub literal: bool,
+    pub ignore_case: bool,
+    /// Restrict the search to one PARA folder (Projects, Areas, Resources, Archives).
+    pub folder: Option<String>,
+    /// Restrict the search to one project directory (active or archived).
+    pub project: Option<String>,
+    /// Lines of context to include before and after each match.
+    pub context: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// File path relative to the Notes root.
+    pub path: PathBuf,
+    /// 1-based line number of the match.
+    pub line: usize,
+    pub text: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Search note contents under the canonical Notes root.
+pub fn search_notes(
+    notes_dir: Option<PathBuf>,
+    pattern: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SearchMatch>> {
+    if options.folder.is_some() && options.project.is_some() {
+        bail!("Use either a folder scope or a project scope, not both");
+    }
+
+    let root = canonical_notes_root(notes_dir)?;
+    let regex = build_regex(pattern, options)?;
+
+    let start = match (&options.folder, &options.project) {
+        (Some(folder), _) => {
+            let Some(name) = PARA_FOLDERS
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(folder))
+            else {
+                bail!(
+                    "Unknown folder {folder}; expected one of {}",
+                    PARA_FOLDERS.join(", ")
+                );
+            };
+            root.join(name)
+        }
+        (None, Some(project)) => {
+            let paths = NotesPaths::from_root(root.clone());
+            resolve_project(&paths, project)?.dir
+        }
+        (None, None) => root.clone(),
+    };
+
+    let mut files = Vec::new();
+    if start.is_dir() {
+        let start = fs::canonicalize(&start)
+            .with_context(|| format!("Failed to resolve {}", start.display()))?;
+        ensure_contained(&root, &start)?;
+        collect_files(&root, &start, &mut files)?;
+    }
+
+    let mut matches = Vec::new();
+    for file in files {
+        // Non-UTF-8 files (images, PDFs) are not searchable notes.
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let lines = content.lines().collect::<Vec<&str>>();
+        let relative = file.strip_prefix(&root).unwrap_or(&file).to_path_buf();
+        for (index, line) in lines.iter().enumerate() {
+            if !regex.is_match(line) {
+                continue;
+            }
+            let before_start = index.saturating_sub(options.context);
+            let after_end = (index + 1 + options.context).min(lines.len());
+            matches.push(SearchMatch {
+                path: relative.clone(),
+                line: index + 1,
+                text: line.to_string(),
+                before: to_strings(&lines[before_start..index]),
+                after: to_strings(&lines[(index + 1)..after_end]),
+            });
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Render search results; `None` prints grep-style `path:line:text` output.
+pub fn render_search(matches: &[SearchMatch], format: Option<OutputFormat>) -> String {
+    match format {
+        None => render_grep(matches),
+        Some(OutputFormat::Json) => render_json(&Value::Array(
+            matches
+                .iter()
+                .map(|found| {
+                    json!({
+                        "path": found.path,
+                        "line": found.line,
+                        "text": found.text,
+                        "before": found.before,
+                        "after": found.after,
+                    })
+                })
+                .collect(),
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(
+            &matches
+                .iter()
+                .map(|found| {
+                    vec![
+                        found.path.display().to_string(),
+                        found.line.to_string(),
+                        found.text.clone(),
+                    ]
+                })
+                .collect::<Vec<Vec<String>>>(),
+        ),
+        Some(OutputFormat::Table) => render_table(
+            &["path", "line", "text"],
+            &matches
+                .iter()
+                .map(|found| {
+                    vec![
+                        found.path.display().to_string(),
+                        found.line.to_string(),
+                        found.text.trim().to_string(),
+                    ]
+                })
+                .collect::<Vec<Vec<String>>>(),
+        ),
+    }
+}
+
+fn render_grep(matches: &[SearchMatch]) -> String {
+    // `(path, line number, text, is a match)` in output order; `None` is a `--` break.
+    let mut lines: Vec<Option<(&Path, usize, &str, bool)>> = Vec::new();
+    let mut last: Option<(&Path, usize)> = None;
+    let has_context = matches
+        .iter()
+        .any(|found| !found.before.is_empty() || !found.after.is_empty());
+
+    for found in matches {
+        let path = found.path.as_path();
+        let first_line = found.line - found.before.len();
+        // Lines already printed for this file (from overlapping context) are not
+        // repeated, but a match printed earlier as context becomes a match line.
+        let printed_through = match last {
+            Some((last_path, last_line)) if last_path == path => last_line,
+            _ => 0,
+        };
+        if has_context && last.is_some() && first_line > printed_through + 1 {
+            lines.push(None);
+        }
+
+        let numbered = found
+            .before
+            .iter()
+            .enumerate()
+            .map(|(offset, text)| (first_line + offset, text.as_str(), false))
+            .chain([(found.line, found.text.as_str(), true)])
+            .chain(
+                found
+                    .after
+                    .iter()
+                    .enumerate()
+                    .map(|(offset, text)| (found.line + 1 + offset, text.as_str(), false)),
+            );
+        for (number, text, is_match) in numbered {
+            if number > printed_through {
+                lines.push(Some((path, number, text, is_match)));
+            } else if is_match
+                && let Some(Some(line)) = lines.iter_mut().rev().find(|line| {
+                    line.is_some_and(|(line_path, line_number, _, _)| {
+                        line_path == path && line_number == number
+                    })
+                })
+            {
+                line.3 = true;
+            }
+        }
+        let through = (found.line + found.after.len()).max(printed_through);
+        last = Some((path, through));
+    }
+
+    lines
+        .into_iter()
+        .map(|line| match line {
+            Some((path, number, text, true)) => format!("{}:{number}:{text}\n", path.display()),
+            Some((path, number, text, false)) => format!("{}-{number}-{text}\n", path.display()),
+            None => "--\n".to_string(),
+        })
+        .collect()
+}
+
+fn build_regex(pattern: &str, options: &SearchOptions) -> Result<Regex> {
+    let source = if options.literal {
+        regex::escape(pattern)
+    } else {
+        pattern.to_string()
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(options.ignore_case)
+        .build()
+        .with_context(|| format!("Invalid search pattern {pattern}"))
+}
+
+/// Recursively collect files under `dir`, skipping hidden entries and
+/// anything (including symlink targets) outside the Notes root.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .collect::<std::io::Result<Vec<fs::DirEntry>>>()
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        } else if file_type.is_symlink() {
+            // Follow symlinked files only when they stay inside the root;
+            // symlinked directories are not walked to avoid cycles.
+            let Ok(target) = fs::canonicalize(&path) else {
+                continue;
+            };
+            if target.is_file() && ensure_contained(root, &target).is_ok() {
+                files.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_strings(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup() -> (tempfile::TempDir, PathBuf) {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("Notes");
+        let project = root.join("Projects").join("proj-1-retry");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(root.join("Areas")).unwrap();
+        fs::write(
+            project.join("README.md"),
+            "# PROJ-1: Retry\n\n## Notes\n- retry with backoff (a+b)\n- done\n",
+        )
+        .unwrap();
+        fs::write(root.join("Areas").join("ops.md"), "Retry budget\n").unwrap();
+        fs::write(
+            root.join("Projects").join("_registry.md"),
+            format!(
+                "{}| PROJ-1 | Retry | active | 2026-01-01 | [proj-1-retry/README.md](proj-1-retry/README.md) |\n",
+                crate::REGISTRY_HEADER
+            ),
+        )
+        .unwrap();
+        (temp, root)
+    }
+
+    #[test]
+    fn searches_with_regex_literal_and_case_options() {
+        let (_temp, root) = setup();
+
+        let found =
+            search_notes(Some(root.clone()), "back+off", &SearchOptions::default()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].path,
+            Path::new("Projects").join("proj-1-retry").join("README.md")
+        );
+        assert_eq!(found[0].line, 4);
+
+        let options = SearchOptions {
+            ignore_case: true,
+            ..SearchOptions::default()
+        };
+        let found = search_notes(Some(root.clone()), "^retry budget", &options).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, Path::new("Areas").join("ops.md"));
+
+        let options = SearchOptions {
+            literal: true,
+            ..SearchOptions::default()
+        };
+        let found = search_notes(Some(root), "(a+b)", &options).unwrap();
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn scopes_by_folder_and_project() {
+        let (_temp, root) = setup();
+        let options = SearchOptions {
+            ignore_case: true,
+            folder: Some("areas".to_string()),
+            ..SearchOptions::default()
+        };
+        let found = search_notes(Some(root.clone()), "retry", &options).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, Path::new("Areas").join("ops.md"));
+
+        let options = SearchOptions {
+            ignore_case: true,
+            project: Some("PROJ-1".to_string()),
+            context: 1,
+            ..SearchOptions::default()
+        };
+        let found = search_notes(Some(root.clone()), "retry", &options).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].before, vec!["## Notes".to_string()]);
+        assert_eq!(found[1].after, vec!["- done".to_string()]);
+
+        let options = SearchOptions {
+            folder: Some("Secrets".to_string()),
+            ..SearchOptions::default()
+        };
+        assert!(search_notes(Some(root), "x", &options).is_err());
+    }
+
+    #[test]
+    fn grep_output_merges_overlapping_context() {
+        let matches = vec![
+            SearchMatch {
+                path: PathBuf::from("a.md"),
+                line: 2,
+                text: "two".to_string(),
+                before: vec!["one".to_string()],
+                after: vec!["three".to_string()],
+            },
+            SearchMatch {
+                path: PathBuf::from("a.md"),
+                line: 3,
+                text: "three".to_string(),
+                before: vec!["two".to_string()],
+                after: vec![],
+            },
+            SearchMatch {
+                path: PathBuf::from("a.md"),
+                line: 9,
+                text: "nine".to_string(),
+                before: vec![],
+                after: vec![],
+            },
+        ];
+        assert_eq!(
+            render_search(&matches, None),
+            "a.md-1-one\na.md:2:two\na.md:3:three\n--\na.md:9:nine\n"
+        );
+    }
+}
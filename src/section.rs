@@ -0,0 +1,238 @@
+use anyhow::{Result, bail};
+use serde_json::json;
+use std::ops::Range;
+
+use crate::OutputFormat;
+use crate::output::{render_json, render_table, render_tsv};
+
+/// An ATX markdown heading (`## Title`) in a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+impl Heading {
+    /// The heading as written in markdown, e.g. `## Notes`.
+    pub fn marker(&self) -> String {
+        format!("{} {}", "#".repeat(self.level), self.title)
+    }
+}
+
+/// Headings outside frontmatter and fenced code blocks.
+pub(crate) fn headings(lines: &[String]) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut index = 0;
+    if lines.first().is_some_and(|line| line.trim_end() == "---")
+        && let Some(close) = lines
+            .iter()
+            .skip(1)
+            .position(|line| line.trim_end() == "---")
+    {
+        index = close + 2;
+    }
+
+    let mut fence: Option<&str> = None;
+    for (offset, line) in lines.iter().enumerate().skip(index) {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+            continue;
+        }
+        if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+            continue;
+        }
+        if let Some((level, title)) = parse_heading(line) {
+            headings.push(Heading {
+                level,
+                title,
+                line: offset + 1,
+            });
+        }
+    }
+    headings
+}
+
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    // Up to three spaces of indentation, as in CommonMark.
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let level = rest.chars().take_while(|ch| *ch == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = &rest[level..];
+    if !text.is_empty() && !text.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = text.trim().trim_end_matches('#').trim_end();
+    Some((level, title.to_string()))
+}
+
+/// 0-based line range of a section: its heading through the line before the
+/// next heading of the same or a higher level.
+///
+/// `query` is a heading title (`Notes`) or a full heading (`## Notes`) to
+/// also pin the level; titles compare case-insensitively.
+pub(crate) fn find_section(lines: &[String], query: &str) -> Result<(Heading, Range<usize>)> {
+    let all = headings(lines);
+    let (level, title) = match parse_heading(query.trim()) {
+        Some((level, title)) => (Some(level), title),
+        None => (None, query.trim().to_string()),
+    };
+    if title.is_empty() {
+        bail!("Section heading must not be empty");
+    }
+
+    let matches = all
+        .iter()
+        .filter(|heading| {
+            heading.title.eq_ignore_ascii_case(&title)
+                && level.is_none_or(|level| heading.level == level)
+        })
+        .collect::<Vec<&Heading>>();
+    let heading = match matches.as_slice() {
+        [heading] => (*heading).clone(),
+        [] if all.is_empty() => bail!("Section not found: {query}; the note has no headings"),
+        [] => bail!(
+            "Section not found: {query}; available sections: {}",
+            all.iter()
+                .map(Heading::marker)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        _ => bail!(
+            "Section {query} is ambiguous; matching headings at lines {}",
+            matches
+                .iter()
+                .map(|heading| heading.line.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    };
+
+    let end = all
+        .iter()
+        .find(|next| next.line > heading.line && next.level <= heading.level)
+        .map_or(lines.len(), |next| next.line - 1);
+    let range = heading.line - 1..end;
+    Ok((heading, range))
+}
+
+/// Where appended section text goes: after the section's last non-blank line,
+/// so the blank line before the next heading stays in place.
+pub(crate) fn section_insert_point(lines: &[String], section: &Range<usize>) -> usize {
+    let body_start = section.start + 1;
+    let mut end = section.end;
+    while end > body_start && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    end
+}
+
+pub fn render_outline(headings: &[Heading], format: Option<OutputFormat>) -> String {
+    let rows = headings
+        .iter()
+        .map(|heading| {
+            vec![
+                heading.line.to_string(),
+                heading.level.to_string(),
+                heading.title.clone(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    match format {
+        None => {
+            if headings.is_empty() {
+                return "No headings found.\n".to_string();
+            }
+            // Indent relative to the shallowest heading so `##`-only notes start flush.
+            let base = headings
+                .iter()
+                .map(|heading| heading.level)
+                .min()
+                .unwrap_or(1);
+            headings
+                .iter()
+                .map(|heading| {
+                    format!(
+                        "{:>6}\t{}{}\n",
+                        heading.line,
+                        "  ".repeat(heading.level - base),
+                        heading.marker()
+                    )
+                })
+                .collect()
+        }
+        Some(OutputFormat::Json) => render_json(&json!(
+            headings
+                .iter()
+                .map(|heading| json!({
+                    "line": heading.line,
+                    "level": heading.level,
+                    "title": heading.title,
+                }))
+                .collect::<Vec<_>>()
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => render_table(&["line", "level", "title"], &rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn finds_sections_by_title_or_level() {
+        let note = lines(
+            "---\ntitle: x\n# not a heading\n---\n# Title\n\n## Notes\n- a\n### Detail\n- b\n\n## Next\n```\n# code\n```\n",
+        );
+        let (heading, range) = find_section(&note, "notes").unwrap();
+        assert_eq!(heading.line, 7);
+        assert_eq!(range, 6..11);
+        assert_eq!(section_insert_point(&note, &range), 10);
+
+        let (_, range) = find_section(&note, "## Next").unwrap();
+        assert_eq!(range, 11..note.len());
+        assert_eq!(
+            headings(&note)
+                .iter()
+                .map(Heading::marker)
+                .collect::<Vec<String>>(),
+            vec!["# Title", "## Notes", "### Detail", "## Next"]
+        );
+
+        let err = find_section(&note, "### Notes").unwrap_err().to_string();
+        assert!(err.contains("available sections: # Title, ## Notes"));
+
+        assert_eq!(
+            render_outline(&headings(&note), None),
+            "     5\t# Title\n     7\t  ## Notes\n     9\t    ### Detail\n    12\t  ## Next\n"
+        );
+    }
+
+    #[test]
+    fn ambiguous_sections_list_lines() {
+        let note = lines("## Notes\na\n## Notes\nb\n");
+        let err = find_section(&note, "Notes").unwrap_err().to_string();
+        assert!(err.contains("lines 1, 3"));
+    }
+}
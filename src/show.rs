@@ -0,0 +1,332 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::output::{render_json, render_table, render_tsv};
+use crate::{Frontmatter, NotesPaths, OutputFormat, resolve_project, section};
+
+/// README sections `pman show` prints, in order.
+const SHOWN_SECTIONS: [&str; 2] = ["## Summary", "## Next"];
+
+/// Everything `pman show` reports about one project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectSummary {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub created: Option<String>,
+    /// Whole days since `created`.
+    pub age_days: Option<i64>,
+    /// Latest modification time of any file in the project directory.
+    pub modified: Option<String>,
+    pub archived: bool,
+    pub dir: PathBuf,
+    pub note: Option<PathBuf>,
+    pub frontmatter: Vec<(String, String)>,
+    /// Files under the project directory, relative to it.
+    pub files: Vec<String>,
+    /// Markdown files other than the main note (`pman note add`).
+    pub notes: Vec<String>,
+    /// Every other file (`pman attach`).
+    pub attachments: Vec<String>,
+    /// `(heading, body)` for each of the shown README sections present.
+    pub sections: Vec<(String, String)>,
+}
+
+pub fn show_project(paths: &NotesPaths, input: &str) -> Result<ProjectSummary> {
+    let project = resolve_project(paths, input)?;
+    let note = Some(project.note(paths)).filter(|note| note.is_file());
+    let content = match &note {
+        Some(note) => fs::read_to_string(note)
+            .with_context(|| format!("Failed to read note {}", note.display()))?,
+        None => String::new(),
+    };
+    let frontmatter = Frontmatter::parse(&content);
+
+    let status = project
+        .entry
+        .as_ref()
+        .map(|entry| entry.status.clone())
+        .or_else(|| frontmatter.get("status"))
+        .unwrap_or_else(|| {
+            if project.archived {
+                "archived"
+            } else {
+                "unknown"
+            }
+            .to_string()
+        });
+    let created = project
+        .entry
+        .as_ref()
+        .map(|entry| entry.created.clone())
+        .or_else(|| frontmatter.get("created"))
+        .or_else(|| {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix("**Created**:"))
+                .map(|value| value.trim().to_string())
+        })
+        .filter(|created| !created.is_empty());
+    let age_days = created
+        .as_deref()
+        .and_then(|created| NaiveDate::parse_from_str(created, "%Y-%m-%d").ok())
+        .map(|created| (Local::now().date_naive() - created).num_days());
+
+    let mut files = Vec::new();
+    let mut latest = None;
+    collect_files(&project.dir, &project.dir, &mut files, &mut latest)?;
+    files.sort();
+    let main_note = note
+        .as_deref()
+        .and_then(|note| note.strip_prefix(&project.dir).ok())
+        .map(|note| note.to_string_lossy().replace('\\', "/"));
+    let (notes, attachments): (Vec<String>, Vec<String>) = files
+        .iter()
+        .filter(|file| main_note.as_deref() != Some(file.as_str()))
+        .cloned()
+        .partition(|file| file.ends_with(".md"));
+    let modified = latest.map(|time: SystemTime| {
+        DateTime::<Local>::from(time)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    });
+
+    let (lines, _) = crate::split_lines(&content);
+    let sections = SHOWN_SECTIONS
+        .iter()
+        .filter_map(|heading| {
+            let (heading, range) = section::find_section(&lines, heading).ok()?;
+            let end = section::section_insert_point(&lines, &range);
+            Some((heading.marker(), lines[range.start + 1..end].join("\n")))
+        })
+        .collect();
+
+    Ok(ProjectSummary {
+        id: project.id,
+        name: project.name,
+        status,
+        created,
+        age_days,
+        modified,
+        archived: project.archived,
+        dir: project.dir,
+        note,
+        frontmatter: frontmatter.entries(),
+        files,
+        notes,
+        attachments,
+        sections,
+    })
+}
+
+/// Recursively list non-hidden files relative to `base`, tracking the newest mtime.
+fn collect_files(
+    base: &Path,
+    dir: &Path,
+    files: &mut Vec<String>,
+    latest: &mut Option<SystemTime>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(base, &path, files, latest)?;
+            continue;
+        }
+        if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+            *latest = Some(latest.map_or(modified, |latest| latest.max(modified)));
+        }
+        let relative = path.strip_prefix(base).unwrap_or(&path);
+        files.push(
+            relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        );
+    }
+    Ok(())
+}
+
+pub fn render_show(summary: &ProjectSummary, format: Option<OutputFormat>) -> String {
+    let created = match (&summary.created, summary.age_days) {
+        (Some(created), Some(0)) => format!("{created} (today)"),
+        (Some(created), Some(1)) => format!("{created} (1 day ago)"),
+        (Some(created), Some(days)) => format!("{created} ({days} days ago)"),
+        (Some(created), None) => created.clone(),
+        (None, _) => "-".to_string(),
+    };
+    let note = summary
+        .note
+        .as_ref()
+        .map_or("-".to_string(), |note| note.display().to_string());
+    let fields = [
+        ("id", summary.id.clone()),
+        ("name", summary.name.clone()),
+        ("status", summary.status.clone()),
+        ("created", created),
+        (
+            "modified",
+            summary.modified.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "location",
+            if summary.archived {
+                "archived"
+            } else {
+                "active"
+            }
+            .to_string(),
+        ),
+        ("note", note),
+        ("directory", summary.dir.display().to_string()),
+    ];
+
+    match format {
+        None => {
+            let mut output = format!("{}: {}\n", summary.id, summary.name);
+            for (key, value) in fields.iter().skip(2) {
+                let label = format!("{}{}:", key[..1].to_ascii_uppercase(), &key[1..]);
+                output.push_str(&format!("{label:<10} {value}\n"));
+            }
+            if !summary.frontmatter.is_empty() {
+                output.push_str("\nFrontmatter:\n");
+                for (key, value) in &summary.frontmatter {
+                    output.push_str(&format!("  {key}: {value}\n"));
+                }
+            }
+            for (label, files) in [
+                ("Notes", &summary.notes),
+                ("Attachments", &summary.attachments),
+            ] {
+                output.push_str(&format!("\n{label}:\n"));
+                if files.is_empty() {
+                    output.push_str("  (none)\n");
+                }
+                for file in files {
+                    output.push_str(&format!("  {file}\n"));
+                }
+            }
+            for (heading, body) in &summary.sections {
+                output.push_str(&format!("\n{heading}\n"));
+                if !body.is_empty() {
+                    output.push_str(body);
+                    output.push('\n');
+                }
+            }
+            output
+        }
+        Some(OutputFormat::Json) => render_json(&json!({
+            "id": summary.id,
+            "name": summary.name,
+            "status": summary.status,
+            "created": summary.created,
+            "age_days": summary.age_days,
+            "modified": summary.modified,
+            "archived": summary.archived,
+            "dir": summary.dir,
+            "note": summary.note,
+            "frontmatter": summary
+                .frontmatter
+                .iter()
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .collect::<Map<String, Value>>(),
+            "files": summary.files,
+            "notes": summary.notes,
+            "attachments": summary.attachments,
+            "sections": summary
+                .sections
+                .iter()
+                .map(|(heading, body)| (heading.clone(), Value::String(body.clone())))
+                .collect::<Map<String, Value>>(),
+        })),
+        Some(format) => {
+            let mut rows = fields
+                .iter()
+                .map(|(key, value)| vec![key.to_string(), value.clone()])
+                .collect::<Vec<Vec<String>>>();
+            rows.extend(
+                summary
+                    .frontmatter
+                    .iter()
+                    .map(|(key, value)| vec![format!("meta.{key}"), value.clone()]),
+            );
+            rows.extend(
+                summary
+                    .files
+                    .iter()
+                    .map(|file| vec!["file".to_string(), file.clone()]),
+            );
+            match format {
+                OutputFormat::Tsv => render_tsv(&rows),
+                _ => render_table(&["field", "value"], &rows),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::REGISTRY_HEADER;
+    use tempfile::tempdir;
+
+    #[test]
+    fn summarizes_registry_frontmatter_files_and_sections() {
+        let temp = tempdir().unwrap();
+        let paths = NotesPaths::from_root(temp.path().to_path_buf());
+        let dir = paths.projects_dir.join("proj-7-payment-retry");
+        fs::create_dir_all(dir.join("notes")).unwrap();
+        fs::write(
+            dir.join("README.md"),
+            "---\nstatus: active\narea: payments\n---\n\n# PROJ-7: Payment Retry\n\n## Summary\n- Retry failed charges\n\n## Notes\n- long log\n\n## Next\n- Ship it\n",
+        )
+        .unwrap();
+        fs::write(dir.join("notes").join("call.md"), "call").unwrap();
+        fs::write(dir.join("flow.png"), "png").unwrap();
+        fs::write(dir.join(".DS_Store"), "").unwrap();
+        fs::write(
+            &paths.registry,
+            format!(
+                "{REGISTRY_HEADER}| PROJ-7 | Payment Retry | active | 2026-01-05 | [proj-7-payment-retry/README.md](proj-7-payment-retry/README.md) |\n"
+            ),
+        )
+        .unwrap();
+
+        let summary = show_project(&paths, "payment-retry").unwrap();
+        assert_eq!(summary.id, "PROJ-7");
+        assert_eq!(summary.created.as_deref(), Some("2026-01-05"));
+        assert!(summary.age_days.is_some());
+        assert!(summary.modified.is_some());
+        assert_eq!(
+            summary.files,
+            vec!["README.md", "flow.png", "notes/call.md"]
+        );
+        assert_eq!(summary.notes, vec!["notes/call.md"]);
+        assert_eq!(summary.attachments, vec!["flow.png"]);
+        assert_eq!(
+            summary.sections,
+            vec![
+                (
+                    "## Summary".to_string(),
+                    "- Retry failed charges".to_string()
+                ),
+                ("## Next".to_string(), "- Ship it".to_string()),
+            ]
+        );
+
+        let output = render_show(&summary, None);
+        assert!(output.starts_with("PROJ-7: Payment Retry\nStatus:    active\n"));
+        assert!(output.contains("\nFrontmatter:\n  status: active\n  area: payments\n"));
+        assert!(output.contains("\nNotes:\n  notes/call.md\n\nAttachments:\n  flow.png\n"));
+        assert!(!output.contains("long log"));
+    }
+}
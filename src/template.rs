@@ -0,0 +1,181 @@
+use anyhow::{Context, Error, Result, bail};
+use regex::{Captures, Regex};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Placeholders filled in by `pman new`; `--var` may not redefine them.
+pub(crate) const BUILTIN_PLACEHOLDERS: [&str; 5] = ["id", "name", "created", "status", "area"];
+
+/// A named project note template plus user-supplied `--var` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectTemplate {
+    pub name: String,
+    pub vars: Vec<TemplateVar>,
+}
+
+/// A `key=value` pair substituted for `{key}` in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateVar {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for TemplateVar {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((key, replacement)) = value.split_once('=') else {
+            bail!("Invalid template variable {value}; expected key=value");
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+        {
+            bail!("Invalid template variable name {key:?}; use letters, digits, '-' or '_'");
+        }
+        if BUILTIN_PLACEHOLDERS.contains(&key) {
+            bail!("Template variable {key} is set by pman and cannot be overridden");
+        }
+        Ok(Self {
+            key: key.to_string(),
+            value: replacement.to_string(),
+        })
+    }
+}
+
+/// Template directories in lookup order: `templates_dir` from the config,
+/// `Notes/Resources/Templates/`, then the workspace `.pman/templates/` next to
+/// the Notes root.
+pub(crate) fn template_dirs(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = crate::config::setting("templates_dir")? {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs.push(root.join("Resources").join("Templates"));
+    if let Some(workspace) = root.parent() {
+        dirs.push(workspace.join(".pman").join("templates"));
+    }
+    Ok(dirs)
+}
+
+/// Resolve a template name (with or without `.md`) to a file.
+pub(crate) fn find_template(root: &Path, name: &str) -> Result<PathBuf> {
+    if let Some(path) = lookup_template(root, name)? {
+        return Ok(path);
+    }
+
+    let name = name.trim();
+    let available = available_templates(root)?;
+    if available.is_empty() {
+        bail!(
+            "Template not found: {name}; add templates to Notes/Resources/Templates/ or .pman/templates/"
+        );
+    }
+    bail!(
+        "Template not found: {name}; available templates: {}",
+        available.join(", ")
+    )
+}
+
+/// Like [`find_template`], but `None` when no template directory has `name`.
+pub(crate) fn lookup_template(root: &Path, name: &str) -> Result<Option<PathBuf>> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("Invalid template name {name:?}");
+    }
+    let file_name = if name.ends_with(".md") {
+        name.to_string()
+    } else {
+        format!("{name}.md")
+    };
+
+    Ok(template_dirs(root)?
+        .into_iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file()))
+}
+
+/// Template names (without `.md`) across all template directories.
+pub(crate) fn available_templates(root: &Path) -> Result<Vec<String>> {
+    let mut names = template_dirs(root)?
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_suffix(".md"))
+                .map(str::to_string)
+        })
+        .collect::<Vec<String>>();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+pub(crate) fn load_template(root: &Path, name: &str) -> Result<String> {
+    let path = find_template(root, name)?;
+    fs::read_to_string(&path).with_context(|| format!("Failed to read template {}", path.display()))
+}
+
+/// Replace `{key}` placeholders in one pass; unknown placeholders are left as-is.
+pub(crate) fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let placeholder = Regex::new(r"\{([A-Za-z0-9_-]+)\}").expect("valid placeholder regex");
+    placeholder
+        .replace_all(template, |captures: &Captures| {
+            let key = &captures[1];
+            values
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn renders_known_placeholders_once() {
+        let rendered = render_template(
+            "# {id}: {name}\n{ticket} {unknown} {{id}}\n",
+            &[("id", "PROJ-1"), ("name", "{ticket}"), ("ticket", "PAY-9")],
+        );
+        assert_eq!(rendered, "# PROJ-1: {ticket}\nPAY-9 {unknown} {PROJ-1}\n");
+    }
+
+    #[test]
+    fn finds_templates_in_notes_then_workspace() {
+        let temp = tempdir().unwrap();
+        let root = temp.path().join("Notes");
+        let notes_templates = root.join("Resources").join("Templates");
+        let workspace_templates = temp.path().join(".pman").join("templates");
+        fs::create_dir_all(&notes_templates).unwrap();
+        fs::create_dir_all(&workspace_templates).unwrap();
+        fs::write(notes_templates.join("spike.md"), "notes").unwrap();
+        fs::write(workspace_templates.join("spike.md"), "workspace").unwrap();
+        fs::write(workspace_templates.join("rfc.md"), "rfc").unwrap();
+
+        assert_eq!(load_template(&root, "spike").unwrap(), "notes");
+        assert_eq!(load_template(&root, "rfc.md").unwrap(), "rfc");
+        let err = find_template(&root, "bugfix").unwrap_err().to_string();
+        assert!(err.contains("available templates: rfc, spike"));
+        assert!(find_template(&root, "../secret").is_err());
+    }
+
+    #[test]
+    fn template_vars_reject_builtin_names() {
+        let var = "ticket=PAY-12".parse::<TemplateVar>().unwrap();
+        assert_eq!(var.value, "PAY-12");
+        assert!("status=done".parse::<TemplateVar>().is_err());
+        assert!("ticket".parse::<TemplateVar>().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::OutputFormat;
+use crate::config::{home_dir, load_document, resolve_path, user_config};
+use crate::output::{render_json, render_table, render_tsv};
+
+pub(crate) const VAULT_ENV_VAR: &str = "PMAN_VAULT";
+
+/// Vault chosen with `--vault` for the rest of the process.
+static SELECTED_VAULT: OnceLock<String> = OnceLock::new();
+
+/// A named Notes root from the `[vaults]` table of the user config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vault {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Vaults defined in the user config, in file order.
+pub fn vaults() -> Result<Vec<Vault>> {
+    match user_config() {
+        Some(path) if path.is_file() => vaults_in(&path),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Use vault `name` for every Notes root lookup in this process (`--vault`).
+pub fn select_vault(name: &str) -> Result<()> {
+    vault_dir(name)?;
+    if SELECTED_VAULT.set(name.to_string()).is_err() {
+        bail!("A vault was already selected");
+    }
+    Ok(())
+}
+
+pub(crate) fn selected_vault() -> Option<&'static str> {
+    SELECTED_VAULT.get().map(String::as_str)
+}
+
+/// The vault in effect: `--vault`, then `PMAN_VAULT` or `vault` in the config.
+pub fn active_vault() -> Result<Option<String>> {
+    match selected_vault() {
+        Some(name) => Ok(Some(name.to_string())),
+        None => crate::config::setting("vault"),
+    }
+}
+
+pub(crate) fn vault_dir(name: &str) -> Result<PathBuf> {
+    let vaults = vaults()?;
+    if let Some(vault) = vaults.iter().find(|vault| vault.name == name) {
+        return Ok(vault.path.clone());
+    }
+    let config = user_config().map_or_else(
+        || "the user config".to_string(),
+        |path| path.display().to_string(),
+    );
+    if vaults.is_empty() {
+        bail!("Unknown vault {name}; no vaults are defined under [vaults] in {config}");
+    }
+    bail!(
+        "Unknown vault {name}; known vaults: {} (defined under [vaults] in {config})",
+        vaults
+            .iter()
+            .map(|vault| vault.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    )
+}
+
+fn vaults_in(path: &Path) -> Result<Vec<Vault>> {
+    let document = load_document(path)?;
+    let Some(item) = document.get("vaults") else {
+        return Ok(Vec::new());
+    };
+    let table = item.as_table_like().with_context(|| {
+        format!(
+            "Invalid vaults in {}: expected a table of name = \"path\"",
+            path.display()
+        )
+    })?;
+    let base = home_dir()
+        .or_else(|| path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    table
+        .iter()
+        .map(|(name, value)| {
+            let value = value.as_str().with_context(|| {
+                format!(
+                    "Invalid vault {name} in {}: expected a path string",
+                    path.display()
+                )
+            })?;
+            Ok(Vault {
+                name: name.to_string(),
+                path: PathBuf::from(resolve_path(value, &base)),
+            })
+        })
+        .collect()
+}
+
+pub fn render_vaults(
+    vaults: &[Vault],
+    active: Option<&str>,
+    format: Option<OutputFormat>,
+) -> String {
+    let is_active = |vault: &Vault| active == Some(vault.name.as_str());
+    let rows = vaults
+        .iter()
+        .map(|vault| {
+            vec![
+                vault.name.clone(),
+                vault.path.display().to_string(),
+                if vault.path.is_dir() { "ok" } else { "missing" }.to_string(),
+                if is_active(vault) { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    match format {
+        None => {
+            if vaults.is_empty() {
+                return "No vaults defined; add them under [vaults] in ~/.config/pman/config.toml\n"
+                    .to_string();
+            }
+            let width = vaults
+                .iter()
+                .map(|vault| vault.name.chars().count())
+                .max()
+                .unwrap_or(0);
+            vaults
+                .iter()
+                .map(|vault| {
+                    format!(
+                        "{} {:<width$}  {}{}\n",
+                        if is_active(vault) { "*" } else { " " },
+                        vault.name,
+                        vault.path.display(),
+                        if vault.path.is_dir() {
+                            ""
+                        } else {
+                            " (missing)"
+                        }
+                    )
+                })
+                .collect()
+        }
+        Some(OutputFormat::Json) => render_json(&json!(
+            vaults
+                .iter()
+                .map(|vault| json!({
+                    "name": vault.name,
+                    "path": vault.path,
+                    "exists": vault.path.is_dir(),
+                    "active": is_active(vault),
+                }))
+                .collect::<Vec<_>>()
+        )),
+        Some(OutputFormat::Tsv) => render_tsv(&rows),
+        Some(OutputFormat::Table) => render_table(&["name", "path", "exists", "active"], &rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_vaults_and_marks_the_active_one() {
+        let temp = tempdir().unwrap();
+        let work = temp.path().join("work-notes");
+        fs::create_dir_all(&work).unwrap();
+        let config = temp.path().join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "pager = \"less\"\n\n[vaults]\nwork = \"{}\"\nclient-x = \"/nonexistent/client-x\"\n",
+                work.display()
+            ),
+        )
+        .unwrap();
+
+        let vaults = vaults_in(&config).unwrap();
+        assert_eq!(
+            vaults
+                .iter()
+                .map(|vault| vault.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["work", "client-x"]
+        );
+        assert_eq!(vaults[0].path, work);
+
+        let output = render_vaults(&vaults, Some("work"), None);
+        assert!(output.starts_with(&format!("* work      {}\n", work.display())));
+        assert!(output.contains("  client-x  /nonexistent/client-x (missing)\n"));
+
+        fs::write(&config, "vaults = \"~/Notes\"\n").unwrap();
+        assert!(vaults_in(&config).is_err());
+    }
+}